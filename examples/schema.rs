@@ -1,10 +1,11 @@
 use cosmwasm_schema::write_api;
-use fuzion_market::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use fuzion_market::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 
 fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         query: QueryMsg,
         execute: ExecuteMsg,
+        migrate: MigrateMsg,
     }
 }
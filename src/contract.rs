@@ -12,8 +12,8 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
-    _msg: InstantiateMsg,
+    info: MessageInfo,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
@@ -21,9 +21,83 @@ pub fn instantiate(
 
     BUCKET_COUNT.save(deps.storage, &1)?;
 
-    FEE_DENOM.save(deps.storage, &FeeDenom::JUNO)?;
+    TRADE_COUNT.save(deps.storage, &1)?;
+
+    STATS.save(deps.storage, &Stats::default())?;
+
+    // Admin defaults to instantiator
+    let admin = msg
+        .admin
+        .map(|a| deps.api.addr_validate(&a))
+        .transpose()
+        .map_err(|_| ContractError::InitInvalidAddr)?
+        .unwrap_or(info.sender);
+
+    // Fee recipient defaults to Community Pool
+    let fee_recipient = msg
+        .fee_recipient
+        .unwrap_or(FeeRecipient::CommunityPool)
+        .validate(deps.api)
+        .map_err(|_| ContractError::InitInvalidAddr)?;
+
+    let limits = msg.limits.unwrap_or_default();
+    limits.check_valid()?;
+
+    let token_lists = msg.token_lists.unwrap_or_default().validate(deps.api)?;
 
-    Ok(Response::new().add_attribute("action", "instantiate"))
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            admin: admin.clone(),
+            pending_admin: None,
+            fee_recipient,
+            limits,
+            pause: Pause::default(),
+            token_lists,
+        },
+    )?;
+
+    msg.fee_config.check_valid()?;
+    FEE_CONFIG.save(deps.storage, &msg.fee_config)?;
+
+    Ok(Response::new().add_attribute("action", "instantiate").add_attribute("admin", admin))
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Migrate
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(mut deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+
+    // Ensure we're migrating the same contract
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::MigrationInvalidContract(stored.contract));
+    }
+
+    let from = Version::parse(&stored.version)
+        .map_err(|_| ContractError::MigrationUnsupported(stored.version.clone()))?;
+    let to = Version::parse(CONTRACT_VERSION)
+        .map_err(|e| ContractError::GenericError(format!("Invalid contract version: {e}")))?;
+
+    // Refuse downgrades
+    if from > to {
+        return Err(ContractError::CannotDowngrade {
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+    }
+
+    let applied = run_migrations(deps.branch(), &from, &msg)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", from.to_string())
+        .add_attribute("to_version", to.to_string())
+        .add_attributes(applied.into_iter().map(|v| ("migrated_from", v))))
 }
 
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -37,6 +111,11 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    // Error if paused, Admin messages are never paused
+    if let Some(action) = msg.pause_action() {
+        CONFIG.load(deps.storage)?.pause.check(&action)?;
+    }
+
     match msg {
         // ~~~~ Receive Wrappers ~~~~ //
         ExecuteMsg::Receive(receive_msg) => execute_receive(deps, &env, &info, &receive_msg),
@@ -49,14 +128,28 @@ pub fn execute(
         ExecuteMsg::AddToListing {
             listing_id,
         } => execute_add_to_listing(deps, Balance::from(info.funds), &info.sender, listing_id),
+        ExecuteMsg::RemoveFromListing {
+            listing_id,
+            assets,
+        } => execute_remove_from_listing(deps, &info.sender, listing_id, assets),
         ExecuteMsg::ChangeAsk {
             listing_id,
-            new_ask,
-        } => execute_change_ask(deps, &info.sender, listing_id, new_ask),
+            new_asks,
+        } => execute_change_ask(deps, &info.sender, listing_id, new_asks),
         ExecuteMsg::Finalize {
             listing_id,
-            seconds,
-        } => execute_finalize(deps, &env, &info.sender, listing_id, seconds),
+            expiration,
+            start,
+        } => execute_finalize(deps, &env, &info.sender, listing_id, expiration, start),
+        ExecuteMsg::Relist {
+            listing_id,
+            expiration,
+            new_asks,
+        } => execute_relist(deps, &env, &info.sender, listing_id, expiration, new_asks),
+        ExecuteMsg::ExtendExpiration {
+            listing_id,
+            expiration,
+        } => execute_extend_expiration(deps, &env, &info.sender, listing_id, expiration),
         ExecuteMsg::DeleteListing {
             listing_id,
         } => execute_delete_listing(deps, &env, info.sender, listing_id),
@@ -70,23 +163,64 @@ pub fn execute(
         } => execute_add_to_bucket(deps, Balance::from(info.funds), &info.sender, bucket_id),
         ExecuteMsg::RemoveBucket {
             bucket_id,
-        } => execute_withdraw_bucket(deps, &info.sender, bucket_id),
+        } => execute_withdraw_bucket(deps, &env, &info.sender, bucket_id),
+        ExecuteMsg::WithdrawFromBucket {
+            bucket_id,
+            assets,
+        } => execute_withdraw_from_bucket(deps, &info.sender, bucket_id, assets),
 
         // ~~~~ Marketplace Executions ~~~~ //
         ExecuteMsg::BuyListing {
             listing_id,
             bucket_id,
-        } => execute_buy_listing(deps, &env, &info.sender, listing_id, bucket_id),
+            ask_index,
+            settle,
+        } => {
+            execute_buy_listing(deps, &env, &info.sender, listing_id, bucket_id, ask_index, settle)
+        }
+        ExecuteMsg::BuyListingWithFunds {
+            listing_id,
+            ask_index,
+        } => execute_buy_listing_with_funds(
+            deps,
+            &env,
+            &info.sender,
+            listing_id,
+            ask_index,
+            &Balance::from(info.funds),
+        ),
         ExecuteMsg::WithdrawPurchased {
             listing_id,
-        } => execute_withdraw_purchased(deps, &info.sender, listing_id),
+        } => execute_withdraw_purchased(deps, &env, &info.sender, listing_id),
+        ExecuteMsg::CancelListing {
+            listing_id,
+        } => execute_cancel_listing(deps, &env, &info.sender, listing_id),
+
+        // ~~~~ Admin Executions ~~~~ //
+        ExecuteMsg::UpdateFeeConfig {
+            fee_config,
+        } => execute_update_fee_config(deps, &info.sender, fee_config),
+        ExecuteMsg::UpdateConfig {
+            fee_recipient,
+            limits,
+        } => execute_update_config(deps, &info.sender, fee_recipient, limits),
+        ExecuteMsg::ProposeAdmin {
+            new_admin,
+        } => execute_propose_admin(deps, &info.sender, &new_admin),
+        ExecuteMsg::AcceptAdmin {} => execute_accept_admin(deps, &info.sender),
+        ExecuteMsg::SetPause {
+            pause,
+        } => execute_set_pause(deps, &info.sender, pause),
+        ExecuteMsg::SetTokenLists {
+            token_lists,
+        } => execute_set_token_lists(deps, &info.sender, token_lists),
     }
 }
 
 // CW20 Filter
 pub fn execute_receive(
     deps: DepsMut,
-    _env: &Env,
+    env: &Env,
     info: &MessageInfo,
     wrapper: &Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
@@ -98,6 +232,11 @@ pub fn execute_receive(
     let msg: ReceiveMsg = from_binary(&wrapper.msg)?;
     let user_wallet = deps.api.addr_validate(&wrapper.sender)?;
 
+    // Error if paused or this cw20 can't be traded
+    let config = CONFIG.load(deps.storage)?;
+    config.pause.check(&msg.pause_action())?;
+    config.token_lists.check_cw20(&info.sender)?;
+
     let balance = Balance::Cw20(Cw20CoinVerified {
         address: info.sender.clone(),
         amount: wrapper.amount,
@@ -114,6 +253,12 @@ pub fn execute_receive(
         ReceiveMsg::AddToBucketCw20 {
             bucket_id,
         } => execute_add_to_bucket(deps, balance, &user_wallet, bucket_id),
+        ReceiveMsg::BuyListingCw20 {
+            listing_id,
+            ask_index,
+        } => {
+            execute_buy_listing_with_funds(deps, env, &user_wallet, listing_id, ask_index, &balance)
+        }
     }
 }
 
@@ -131,6 +276,9 @@ pub fn execute_receive_nft(
     let msg: ReceiveNftMsg = from_binary(&wrapper.msg)?;
     let user_wallet = deps.api.addr_validate(&wrapper.sender)?;
 
+    // Error if this collection can't be traded
+    CONFIG.load(deps.storage)?.token_lists.check_cw721(&info.sender)?;
+
     let incoming_nft: Nft = Nft {
         contract_address: info.sender,
         token_id: wrapper.token_id,
@@ -159,24 +307,69 @@ pub fn execute_receive_nft(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        // QueryMsg::GetAdmin {} => to_binary(&get_admin(deps)?),
-        // QueryMsg::GetConfig {} => to_binary(&get_config(deps)?),
-        // QueryMsg::GetListingInfo {
-        //     listing_id,
-        // } => to_binary(&get_listing_info(deps, listing_id)?),
+        QueryMsg::GetConfig {} => to_binary(&get_config(deps)?),
+        QueryMsg::GetListing {
+            listing_id,
+        } => to_binary(&get_listing(deps, &env, listing_id)?),
+        QueryMsg::GetBucket {
+            owner,
+            bucket_id,
+        } => to_binary(&get_bucket(deps, &owner, bucket_id)?),
 
         // Get all time Listing & Bucket Counts
         QueryMsg::GetCounts {} => to_binary(&get_counts(deps)?),
+        QueryMsg::GetStats {} => to_binary(&get_stats(deps)?),
+        QueryMsg::GetStatTotals {
+            total,
+            start_after,
+            limit,
+        } => to_binary(&get_stat_totals(deps, total, start_after, limit)?),
         QueryMsg::GetFeeDenom {} => to_binary(&get_fee_denom(deps)?),
         QueryMsg::GetListingsByOwner {
             owner,
-        } => to_binary(&get_listings_by_owner(deps, &owner)?),
-        QueryMsg::GetAllListings {} => to_binary(&get_all_listings(deps)?),
+            start_after,
+            limit,
+        } => to_binary(&get_listings_by_owner(deps, &owner, start_after, limit)?),
+        QueryMsg::GetAllListings {
+            start_after,
+            limit,
+        } => to_binary(&get_all_listings(deps, start_after, limit)?),
+        QueryMsg::GetWhitelistedListings {
+            buyer,
+            start_after,
+            limit,
+        } => to_binary(&get_whitelisted_listings(deps, &env, &buyer, start_after, limit)?),
         QueryMsg::GetBuckets {
             bucket_owner,
-        } => to_binary(&get_buckets(deps, &bucket_owner)?),
+            start_after,
+            limit,
+        } => to_binary(&get_buckets(deps, &bucket_owner, start_after, limit)?),
         QueryMsg::GetListingsForMarket {
-            page_num,
-        } => to_binary(&get_listings_for_market(deps, &env, page_num)?),
+            filter,
+            sort,
+            start_after,
+            limit,
+        } => to_binary(&get_listings_for_market(deps, &env, filter, sort, start_after, limit)?),
+        QueryMsg::GetTradesBySeller {
+            seller,
+            start_after,
+            limit,
+        } => to_binary(&get_trades_by_seller(deps, &seller, start_after, limit)?),
+        QueryMsg::GetTradesByBuyer {
+            buyer,
+            start_after,
+            limit,
+        } => to_binary(&get_trades_by_buyer(deps, &buyer, start_after, limit)?),
+        QueryMsg::GetTradesByCollection {
+            collection,
+            start_after,
+            limit,
+        } => to_binary(&get_trades_by_collection(deps, &collection, start_after, limit)?),
+        QueryMsg::GetTradesByTime {
+            from,
+            to,
+            start_after,
+            limit,
+        } => to_binary(&get_trades_by_time(deps, from, to, start_after, limit)?),
     }
 }
@@ -15,9 +15,20 @@ pub enum ContractError {
     #[error("Error Message: Not whitelisted to purchase")]
     NotWhitelisted {},
 
+    #[error("Error Message: Listing has no ask at index {index}")]
+    InvalidAskIndex {
+        index: u32,
+    },
+
     #[error("Error Message: One or more invalid address formats")]
     InvalidAddressFormat,
 
+    #[error("Error Message: {0} is not a valid token contract")]
+    InvalidTokenContract(String),
+
+    #[error("Error Message: {0} can't be traded on this market")]
+    TokenNotAllowed(String),
+
     #[error("Error Message: To Do Error")]
     ToDo {},
 
@@ -63,6 +74,19 @@ pub enum ContractError {
         x: String,
     },
 
+    #[error("Error Message: Listing cannot be cancelled until {at}")]
+    CancelTooEarly {
+        at: String,
+    },
+
+    #[error("Error Message: Listing can't be bought until {start}")]
+    NotStarted {
+        start: String,
+    },
+
+    #[error("Error Message: Invalid Start")]
+    InvalidStart {},
+
     #[error("Error Message: Listing not purchasable")]
     NotPurchasable {},
 
@@ -77,4 +101,40 @@ pub enum ContractError {
 
     #[error("Error Message: Fee calculation error")]
     FeeCalc,
+
+    #[error("Error Message: Invalid Fee Config: {0}")]
+    InvalidFeeConfig(String),
+
+    #[error("Error Message: Cannot contain more than {max} assets")]
+    TooManyAssets {
+        max: u32,
+    },
+
+    #[error("Error Message: Insufficient balance of {0}")]
+    InsufficientBalance(String),
+
+    #[error("Error Message: Bucket {id} has been used to buy a Listing")]
+    BucketCommitted {
+        id: u64,
+    },
+
+    #[error("Error Message: No pending admin")]
+    NoPendingAdmin {},
+
+    #[error("Error Message: {action} is paused")]
+    Paused {
+        action: String,
+    },
+
+    #[error("Error Message: Cannot migrate from contract {0}")]
+    MigrationInvalidContract(String),
+
+    #[error("Error Message: Cannot migrate from version {0}")]
+    MigrationUnsupported(String),
+
+    #[error("Error Message: Cannot downgrade from {from} to {to}")]
+    CannotDowngrade {
+        from: String,
+        to: String,
+    },
 }
@@ -1,5 +1,182 @@
 use crate::execute_imports::*;
 
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Admin
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Replaces the fee schedule used when a Listing is purchased
+///
+/// Only callable by `Config.admin`
+pub fn execute_update_fee_config(
+    deps: DepsMut,
+    sender: &Addr,
+    fee_config: FeeConfig,
+) -> Result<Response, ContractError> {
+    // Ensure sender is admin
+    if sender != &CONFIG.load(deps.storage)?.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Errors on duplicate denoms / invalid rates
+    fee_config.check_valid()?;
+
+    FEE_CONFIG.save(deps.storage, &fee_config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_fee_config")
+        .add_attribute("fee_denoms", fee_config.fee_denoms.len().to_string()))
+}
+
+/// Updates `Config.fee_recipient` and/or `Config.limits`
+///
+/// Only callable by `Config.admin`
+pub fn execute_update_config(
+    deps: DepsMut,
+    sender: &Addr,
+    fee_recipient: Option<FeeRecipient>,
+    limits: Option<Limits>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // Ensure sender is admin
+    if sender != &config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let fee_recipient = match fee_recipient {
+        None => config.fee_recipient,
+        Some(recipient) => recipient.validate(deps.api)?,
+    };
+
+    let limits = limits.unwrap_or(config.limits);
+    limits.check_valid()?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            fee_recipient,
+            limits,
+            ..config
+        },
+    )?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+/// Step 1 of Admin handover, `new_admin` becomes admin after calling `AcceptAdmin`
+///
+/// Proposing again replaces the previous proposal
+pub fn execute_propose_admin(
+    deps: DepsMut,
+    sender: &Addr,
+    new_admin: &str,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // Ensure sender is admin
+    if sender != &config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let pending = deps.api.addr_validate(new_admin)?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            pending_admin: Some(pending.clone()),
+            ..config
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "propose_admin")
+        .add_attribute("pending_admin", pending))
+}
+
+/// Step 2 of Admin handover, callable only by `Config.pending_admin`
+pub fn execute_accept_admin(deps: DepsMut, sender: &Addr) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let Some(pending) = config.pending_admin.clone() else {
+        return Err(ContractError::NoPendingAdmin {});
+    };
+
+    // Ensure sender is the proposed admin
+    if sender != &pending {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            admin: pending.clone(),
+            pending_admin: None,
+            ..config
+        },
+    )?;
+
+    Ok(Response::new().add_attribute("action", "accept_admin").add_attribute("admin", pending))
+}
+
+/// Replaces `Config.pause`
+///
+/// Only callable by `Config.admin`
+pub fn execute_set_pause(
+    deps: DepsMut,
+    sender: &Addr,
+    pause: Pause,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // Ensure sender is admin
+    if sender != &config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            pause: pause.clone(),
+            ..config
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_pause")
+        .add_attribute("create", pause.create.to_string())
+        .add_attribute("buy", pause.buy.to_string())
+        .add_attribute("withdraw", pause.withdraw.to_string()))
+}
+
+/// Replaces `Config.token_lists`, addresses are validated first
+///
+/// Only callable by `Config.admin`
+pub fn execute_set_token_lists(
+    deps: DepsMut,
+    sender: &Addr,
+    token_lists: TokenLists,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // Ensure sender is admin
+    if sender != &config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let token_lists = token_lists.validate(deps.api)?;
+    let mode = format!("{:?}", token_lists.mode);
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            token_lists,
+            ..config
+        },
+    )?;
+
+    Ok(Response::new().add_attribute("action", "set_token_lists").add_attribute("mode", mode))
+}
+
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // Buckets
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -10,7 +187,7 @@ pub fn execute_create_bucket(
 ) -> Result<Response, ContractError> {
     let count = BUCKET_COUNT.load(deps.storage)?;
     // Check that bucket_id isn't used
-    if BUCKETS.has(deps.storage, (creator.clone(), count)) {
+    if bucketz().has(deps.storage, count) {
         return Err(ContractError::IdAlreadyExists {});
     }
 
@@ -18,14 +195,22 @@ pub fn execute_create_bucket(
     // - Prefer this over normalize to abort rather than alter balance sent
     funds.normalized_check()?;
 
+    // Error if any token can't be traded or funds exceed the asset limit
+    let config = CONFIG.load(deps.storage)?;
+    let funds = GenericBalance::from_balance(funds);
+    config.token_lists.check(&funds)?;
+    funds.check_limits(&config.limits)?;
+
     // Save bucket
-    BUCKETS.save(
+    bucketz().save(
         deps.storage,
-        (creator.clone(), count),
+        count,
         &Bucket {
             owner: creator.clone(),
-            funds: GenericBalance::from_balance(funds),
-            fee_amount: None,
+            funds,
+            fee_amount: vec![],
+            paid_for: None,
+            claimant: None,
         },
     )?;
 
@@ -49,19 +234,21 @@ pub fn execute_create_bucket_cw721(
     let count = BUCKET_COUNT.load(deps.storage)?;
 
     // Check that bucket_id isn't used
-    if BUCKETS.has(deps.storage, (user_wallet.clone(), count)) {
+    if bucketz().has(deps.storage, count) {
         return Err(ContractError::IdAlreadyExists {});
     }
 
     // NFT validation checks are handled in receiver wrapper
     // Save bucket
-    BUCKETS.save(
+    bucketz().save(
         deps.storage,
-        (user_wallet.clone(), count),
+        count,
         &Bucket {
             owner: user_wallet.clone(),
             funds: GenericBalance::from_nft(nft),
-            fee_amount: None,
+            fee_amount: vec![],
+            paid_for: None,
+            claimant: None,
         },
     )?;
 
@@ -87,13 +274,16 @@ pub fn execute_add_to_bucket(
     // - Prefer this over normalize to abort rather than alter balance sent
     funds.normalized_check()?;
 
+    // Error if any token can't be traded
+    CONFIG.load(deps.storage)?.token_lists.check(&GenericBalance::from_balance(&funds))?;
+
     // Ensure bucket exists & Sender is owner
-    let Some(the_bucket) = BUCKETS.may_load(deps.storage, (sender.clone(), bucket_id))? else {
+    let Some(the_bucket) = bucketz().may_load(deps.storage, bucket_id)? else {
         return Err(ContractError::NotFound { typ: "Bucket".to_string(), id: bucket_id.to_string() })
     };
 
     // Authorized check
-    if sender != &the_bucket.owner {
+    if sender != the_bucket.holder() {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -109,14 +299,11 @@ pub fn execute_add_to_bucket(
         }
     }?;
 
+    // Error if bucket now holds more assets than allowed
+    new_bucket.funds.check_limits(&CONFIG.load(deps.storage)?.limits)?;
+
     // Save the updated bucket
-    //BUCKETS.save(deps.storage, (sender.clone(), &bucket_id), &new_bucket)?;
-    BUCKETS.update(deps.storage, (sender.clone(), bucket_id), {
-        |o| match o {
-            Some(_) => Ok(new_bucket),
-            None => Err(ContractError::GenericError("Error during storage update".to_string())),
-        }
-    })?;
+    bucketz().save(deps.storage, bucket_id, &new_bucket)?;
 
     Ok(Response::new()
         .add_attribute("action", "add_funds_to_bucket")
@@ -130,12 +317,12 @@ pub fn execute_add_to_bucket_cw721(
     bucket_id: u64,
 ) -> Result<Response, ContractError> {
     // Ensure bucket exists & Sender is owner
-    let Some(the_bucket) = BUCKETS.may_load(deps.storage, (user_wallet.clone(), bucket_id))? else {
+    let Some(the_bucket) = bucketz().may_load(deps.storage, bucket_id)? else {
         return Err(ContractError::NotFound { typ: "Bucket".to_string(), id: bucket_id.to_string() })
     };
 
     // Authorized check
-    if user_wallet != &the_bucket.owner {
+    if user_wallet != the_bucket.holder() {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -151,13 +338,11 @@ pub fn execute_add_to_bucket_cw721(
         }
     }?;
 
+    // Error if bucket now holds more assets than allowed
+    new_bucket.funds.check_limits(&CONFIG.load(deps.storage)?.limits)?;
+
     // Save updated bucket
-    BUCKETS.update(deps.storage, (user_wallet.clone(), bucket_id), {
-        |o| match o {
-            Some(_) => Ok(new_bucket),
-            None => Err(ContractError::GenericError("Error during storage update".to_string())),
-        }
-    })?;
+    bucketz().save(deps.storage, bucket_id, &new_bucket)?;
 
     Ok(Response::new()
         .add_attribute("action", "execute_add_to_bucket_cw721")
@@ -166,23 +351,25 @@ pub fn execute_add_to_bucket_cw721(
 
 pub fn execute_withdraw_bucket(
     deps: DepsMut,
+    env: &Env,
     user: &Addr,
     bucket_id: u64,
 ) -> Result<Response, ContractError> {
     // Get Bucket
-    let the_bucket: Bucket = BUCKETS.load(deps.storage, (user.clone(), bucket_id))?;
+    let the_bucket: Bucket = bucketz().load(deps.storage, bucket_id)?;
 
-    // Check sender is owner redundant
-    if &the_bucket.owner != user {
+    // Only the seller can withdraw a Bucket that paid for a Listing
+    if the_bucket.holder() != user {
         return Err(ContractError::Unauthorized {});
     }
 
     // Create Send Msgs
     // (fee_amount is added when Bucket is used to buy a Listing)
-    let msgs = the_bucket.withdraw_msgs()?;
+    let fee_recipient = CONFIG.load(deps.storage)?.fee_recipient;
+    let msgs = the_bucket.withdraw_msgs(&env.contract.address, &fee_recipient)?;
 
     // Remove Bucket
-    BUCKETS.remove(deps.storage, (user.clone(), bucket_id));
+    bucketz().remove(deps.storage, bucket_id)?;
 
     Ok(Response::new()
         .add_attribute("action", "empty_bucket")
@@ -190,6 +377,52 @@ pub fn execute_withdraw_bucket(
         .add_messages(msgs))
 }
 
+/// Withdraws `assets` from a Bucket, the remainder stays in the Bucket
+///
+/// Bucket is removed if nothing is left in it
+pub fn execute_withdraw_from_bucket(
+    deps: DepsMut,
+    user: &Addr,
+    bucket_id: u64,
+    assets: GenericBalance,
+) -> Result<Response, ContractError> {
+    // Error if assets contains duplicates or 0 balances
+    assets.normalized_check()?;
+    if assets.is_empty() {
+        return Err(ContractError::NoTokens {});
+    }
+
+    // Get Bucket
+    let mut the_bucket: Bucket = bucketz().load(deps.storage, bucket_id)?;
+
+    if the_bucket.holder() != user {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Fees were calculated on the whole Bucket when it was used to buy a Listing
+    if the_bucket.is_committed() {
+        return Err(ContractError::BucketCommitted {
+            id: bucket_id,
+        });
+    }
+
+    // Errors if Bucket doesn't hold all of assets
+    the_bucket.funds.subtract(&assets)?;
+
+    if the_bucket.funds.is_empty() {
+        bucketz().remove(deps.storage, bucket_id)?;
+    } else {
+        bucketz().save(deps.storage, bucket_id, &the_bucket)?;
+    }
+
+    let msgs = send_tokens_cosmos(user, &assets)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "withdraw_from_bucket")
+        .add_attribute("bucket_id", bucket_id.to_string())
+        .add_messages(msgs))
+}
+
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // Listings
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -204,26 +437,25 @@ pub fn execute_create_listing(
     // - Prefer this over normalize to abort rather than alter balance sent
     funds_sent.normalized_check()?;
 
+    // Error if any token can't be traded
+    let config = CONFIG.load(deps.storage)?;
+    config.token_lists.check(&GenericBalance::from_balance(funds_sent))?;
+
     // Pull incrementor ID
     let count = LISTING_COUNT.load(deps.storage)?;
 
     // Check edge case that incrementor ID is taken
-    if (listingz().idx.id.item(deps.storage, count)?).is_some() {
+    if listingz().has(deps.storage, count) {
         return Err(ContractError::IdAlreadyExists {});
     }
 
-    // Get whitelisted buyer | Errors if invalid address
-    // let wl_buyer = createlistingmsg
-    //     .whitelisted_buyer
-    //     .and_then(|address| Some(deps.api.addr_validate(&address)))
-    //     .transpose()
-    //     .map_err(|_| ContractError::GenericError("Invalid whitelisted buyer".to_string()))?;
+    let limits = config.limits;
 
-    let wl_buyer = createlistingmsg
-        .whitelisted_buyer
-        .map(|address| deps.api.addr_validate(&address))
-        .transpose()
-        .map_err(|_e| ContractError::GenericError("Invalid whitelisted buyer".to_string()))?;
+    // Get buyer restriction | Errors if invalid
+    let buyer_restriction = createlistingmsg
+        .buyer_restriction
+        .map(|restriction| restriction.validate(deps.api, &limits))
+        .transpose()?;
 
     // Error if whitelisted buyer is listing creator?
     // Is there ever a situation where someone might want to do this?
@@ -233,27 +465,31 @@ pub fn execute_create_listing(
     //     }
     // }
 
-    // Check the asking price, errors if invalid
-    //check_valid_genbal(&createlistingmsg.ask)?;
-    createlistingmsg.ask.check_valid()?;
+    // Check the asking prices, errors if invalid or any exceed the asset limit
+    limits.check_asks(deps.as_ref(), &createlistingmsg.asks)?;
+
+    // Error if listed assets exceed the asset limit
+    GenericBalance::from_balance(funds_sent).check_limits(&limits)?;
 
     // Save listing
     listingz().save(
         deps.storage,
-        (user_address, count),
+        count,
         &Listing {
             creator: user_address.clone(),
             id: count,
             finalized_time: None,
-            expiration_time: None,
+            start: None,
+            expiration: None,
             status: Status::BeingPrepared,
             claimant: None,
-            whitelisted_buyer: wl_buyer,
+            buyer_restriction,
             for_sale: GenericBalance::from_balance(funds_sent),
-            ask: createlistingmsg.ask,
-            fee_amount: None,
+            asks: createlistingmsg.asks,
+            fee_amount: vec![],
         },
     )?;
+    update_stats(deps.storage, |s| s.listings.add(&Status::BeingPrepared))?;
 
     // Update count
     // Edge case:
@@ -284,36 +520,39 @@ pub fn execute_create_listing_cw721(
     let count = LISTING_COUNT.load(deps.storage)?;
 
     // Edge case check that ID isn't taken
-    if (listingz().idx.id.item(deps.storage, count)?).is_some() {
+    if listingz().has(deps.storage, count) {
         return Err(ContractError::IdAlreadyExists {});
     }
 
-    // Get whitelisted buyer | Errors if invalid address
-    let wl_buyer = createlistingmsg
-        .whitelisted_buyer
-        .map(|address| deps.api.addr_validate(&address))
-        .transpose()
-        .map_err(|_| ContractError::GenericError("Invalid whitelisted buyer".to_string()))?;
+    let limits = CONFIG.load(deps.storage)?.limits;
+
+    // Get buyer restriction | Errors if invalid
+    let buyer_restriction = createlistingmsg
+        .buyer_restriction
+        .map(|restriction| restriction.validate(deps.api, &limits))
+        .transpose()?;
 
-    // Check the asking price, errors if invalid
-    createlistingmsg.ask.check_valid()?;
+    // Check the asking prices, errors if invalid or any exceed the asset limit
+    limits.check_asks(deps.as_ref(), &createlistingmsg.asks)?;
 
     listingz().save(
         deps.storage,
-        (user_wallet, count),
+        count,
         &Listing {
             creator: user_wallet.clone(),
             id: count,
             finalized_time: None,
-            expiration_time: None,
+            start: None,
+            expiration: None,
             status: Status::BeingPrepared,
             claimant: None,
-            whitelisted_buyer: wl_buyer,
+            buyer_restriction,
             for_sale: GenericBalance::from_nft(nft),
-            ask: createlistingmsg.ask,
-            fee_amount: None,
+            asks: createlistingmsg.asks,
+            fee_amount: vec![],
         },
     )?;
+    update_stats(deps.storage, |s| s.listings.add(&Status::BeingPrepared))?;
 
     LISTING_COUNT
         .update(deps.storage, |old| -> Result<u64, StdError> {
@@ -331,10 +570,10 @@ pub fn execute_change_ask(
     deps: DepsMut,
     user_sender: &Addr,
     listing_id: u64,
-    new_ask: GenericBalance,
+    new_asks: Vec<GenericBalance>,
 ) -> Result<Response, ContractError> {
     // Ensure listing exists, sender is owner, & get listing
-    let Some(listing) = listingz().may_load(deps.storage, (user_sender, listing_id))? else {
+    let Some(listing) = listingz().may_load(deps.storage, listing_id)? else {
         return Err(ContractError::NotFound {
             typ: "Listing".to_string(),
             id: listing_id.to_string()
@@ -361,14 +600,14 @@ pub fn execute_change_ask(
         return Err(ContractError::Unauthorized {});
     }
 
-    // Check the asking price, errors if invalid
-    new_ask.check_valid()?;
+    // Check the asking prices, errors if invalid or any exceed the asset limit
+    CONFIG.load(deps.storage)?.limits.check_asks(deps.as_ref(), &new_asks)?;
 
     listingz().replace(
         deps.storage,
-        (user_sender, listing_id),
+        listing_id,
         Some(&Listing {
-            ask: new_ask,
+            asks: new_asks,
             ..listing.clone()
         }),
         Some(&listing),
@@ -388,8 +627,11 @@ pub fn execute_add_to_listing(
     // Error on dupes / 0 amounts
     balance.normalized_check()?;
 
+    // Error if any token can't be traded
+    CONFIG.load(deps.storage)?.token_lists.check(&GenericBalance::from_balance(&balance))?;
+
     // Ensure listing exists, sender is owner, & get listing
-    let Some(listing) = listingz().may_load(deps.storage, (user_sender, listing_id))? else {
+    let Some(listing) = listingz().may_load(deps.storage, listing_id)? else {
         return Err(ContractError::NotFound {
             typ: "Listing".to_string(),
             id: listing_id.to_string()
@@ -424,12 +666,10 @@ pub fn execute_add_to_listing(
         }
     }?;
 
-    listingz().replace(
-        deps.storage,
-        (user_sender, listing_id),
-        Some(&new_listing),
-        Some(&listing),
-    )?;
+    // Error if listing now holds more assets than allowed
+    new_listing.for_sale.check_limits(&CONFIG.load(deps.storage)?.limits)?;
+
+    listingz().replace(deps.storage, listing_id, Some(&new_listing), Some(&listing))?;
 
     Ok(Response::new()
         .add_attribute("action", "add_funds_to_listing")
@@ -443,7 +683,7 @@ pub fn execute_add_to_listing_cw721(
     listing_id: u64,
 ) -> Result<Response, ContractError> {
     // Ensure listing exists, sender is owner, & get listing
-    let Some(old_listing) = listingz().may_load(deps.storage, (user_wallet, listing_id))? else {
+    let Some(old_listing) = listingz().may_load(deps.storage, listing_id)? else {
         return Err(ContractError::NotFound {
             typ: "Listing".to_string(),
             id: listing_id.to_string(),
@@ -477,26 +717,83 @@ pub fn execute_add_to_listing_cw721(
         }
     }?;
 
+    // Error if listing now holds more assets than allowed
+    new_listing.for_sale.check_limits(&CONFIG.load(deps.storage)?.limits)?;
+
     // Replace old listing with new listing
-    listingz().replace(
-        deps.storage,
-        (user_wallet, listing_id),
-        Some(&new_listing),
-        Some(&old_listing),
-    )?;
+    listingz().replace(deps.storage, listing_id, Some(&new_listing), Some(&old_listing))?;
 
     Ok(Response::default())
 }
 
+/// Sends `assets` back to the Listing creator while it's still `BeingPrepared`
+///
+/// Errors if `for_sale` would be left empty, use `DeleteListing` instead
+pub fn execute_remove_from_listing(
+    deps: DepsMut,
+    user_sender: &Addr,
+    listing_id: u64,
+    assets: GenericBalance,
+) -> Result<Response, ContractError> {
+    // Error if assets contains duplicates or 0 balances
+    assets.normalized_check()?;
+    if assets.is_empty() {
+        return Err(ContractError::NoTokens {});
+    }
+
+    // Ensure listing exists, sender is owner, & get listing
+    let Some(old_listing) = listingz().may_load(deps.storage, listing_id)? else {
+        return Err(ContractError::NotFound {
+            typ: "Listing".to_string(),
+            id: listing_id.to_string(),
+        });
+    };
+
+    // Ensure sender is Creator
+    if user_sender != &old_listing.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Ensure status is InPreperation
+    if old_listing.status != Status::BeingPrepared {
+        return Err(ContractError::AlreadyFinalized {});
+    }
+
+    // Ensure no claimant <not already purchased>
+    if old_listing.claimant.is_some() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Errors if listing doesn't hold all of assets
+    let mut new_listing = old_listing.clone();
+    new_listing.for_sale.subtract(&assets)?;
+
+    if new_listing.for_sale.is_empty() {
+        return Err(ContractError::GenericError(
+            "Cannot remove everything from a Listing, use DeleteListing".to_string(),
+        ));
+    }
+
+    listingz().replace(deps.storage, listing_id, Some(&new_listing), Some(&old_listing))?;
+
+    let msgs = send_tokens_cosmos(user_sender, &assets)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_from_listing")
+        .add_attribute("listing", listing_id.to_string())
+        .add_messages(msgs))
+}
+
 pub fn execute_finalize(
     deps: DepsMut,
     env: &Env,
     sender: &Addr,
     listing_id: u64,
-    seconds: u64,
+    expiration: Expiration,
+    start: Option<Scheduled>,
 ) -> Result<Response, ContractError> {
     // Ensure listing exists, Sender is owner & get listing
-    let Some(listing) = listingz().may_load(deps.storage, (sender, listing_id))? else {
+    let Some(listing) = listingz().may_load(deps.storage, listing_id)? else {
         return Err(ContractError::NotFound {
             typ: "Listing".to_string(),
             id: listing_id.to_string(),
@@ -523,31 +820,181 @@ pub fn execute_finalize(
         return Err(ContractError::Unauthorized {});
     }
 
-    // max expiration is 1209600 seconds <14 days>
-    // min expiration is 600 seconds <10 minutes>
-    if !(600..=1_209_600).contains(&seconds) {
-        return Err(ContractError::InvalidExpiration {});
+    // Error if expiration is outside the configured bounds
+    CONFIG.load(deps.storage)?.limits.expiration.check(&expiration, &env.block)?;
+
+    // Start must be in the future & before expiration
+    if let Some(start) = start {
+        let before_expiration = match (start, expiration) {
+            (Scheduled::AtTime(s), Expiration::AtTime(e)) => s < e,
+            (Scheduled::AtHeight(s), Expiration::AtHeight(e)) => s < e,
+            (_, Expiration::Never {}) => true,
+            // A time can't be ordered against a height
+            _ => false,
+        };
+        if start.is_triggered(&env.block) || !before_expiration {
+            return Err(ContractError::InvalidStart {});
+        }
     }
 
     let finalized_at = env.block.time;
-    let expiration = env.block.time.plus_seconds(seconds);
 
     listingz().replace(
         deps.storage,
-        (sender, listing_id),
+        listing_id,
         Some(&Listing {
             finalized_time: Some(finalized_at),
-            expiration_time: Some(expiration),
+            start,
+            expiration: Some(expiration),
             status: Status::FinalizedReady,
             ..listing.clone()
         }),
         Some(&listing),
     )?;
+    update_stats(deps.storage, |s| {
+        s.listings.moved(&Status::BeingPrepared, &Status::FinalizedReady)
+    })?;
 
     Ok(Response::new()
         .add_attribute("action", "finalize")
         .add_attribute("listing_id", listing_id.to_string())
-        .add_attribute("expiration_seconds", expiration.to_string()))
+        .add_attribute("expiration", expiration.to_string()))
+}
+
+/// Puts an expired, unclaimed Listing back on the market until `expiration`
+///
+/// Assets stay in the Listing, `new_asks` replaces the asks if set
+pub fn execute_relist(
+    deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    listing_id: u64,
+    expiration: Expiration,
+    new_asks: Option<Vec<GenericBalance>>,
+) -> Result<Response, ContractError> {
+    // Ensure listing exists, Sender is owner & get listing
+    let Some(listing) = listingz().may_load(deps.storage, listing_id)? else {
+        return Err(ContractError::NotFound {
+            typ: "Listing".to_string(),
+            id: listing_id.to_string(),
+        });
+    };
+
+    // Ensure sender is creator
+    if sender != &listing.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Ensure no Claimant
+    if listing.claimant.is_some() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Ensure finalized & expired
+    if listing.status != Status::FinalizedReady {
+        return Err(ContractError::NotPurchasable {});
+    }
+    if !listing.is_expired(&env.block) {
+        return Err(ContractError::NotExpired {
+            x: listing.expiration.map_or_else(String::new, |exp| exp.to_string()),
+        });
+    }
+
+    // Error if expiration is outside the configured bounds
+    let limits = CONFIG.load(deps.storage)?.limits;
+    limits.expiration.check(&expiration, &env.block)?;
+
+    let asks = match new_asks {
+        None => listing.asks.clone(),
+        Some(new_asks) => {
+            limits.check_asks(deps.as_ref(), &new_asks)?;
+            new_asks
+        }
+    };
+
+    listingz().replace(
+        deps.storage,
+        listing_id,
+        Some(&Listing {
+            finalized_time: Some(env.block.time),
+            // The old start has passed, it would only be compared against the new expiration
+            start: None,
+            expiration: Some(expiration),
+            asks,
+            ..listing.clone()
+        }),
+        Some(&listing),
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "relist")
+        .add_attribute("listing_id", listing_id.to_string())
+        .add_attribute("expiration", expiration.to_string()))
+}
+
+/// Moves the expiration of a live Listing to `expiration`
+///
+/// New expiration must be later than the current one, and the same kind unless it's `Never`
+pub fn execute_extend_expiration(
+    deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    listing_id: u64,
+    expiration: Expiration,
+) -> Result<Response, ContractError> {
+    // Ensure listing exists, Sender is owner & get listing
+    let Some(listing) = listingz().may_load(deps.storage, listing_id)? else {
+        return Err(ContractError::NotFound {
+            typ: "Listing".to_string(),
+            id: listing_id.to_string(),
+        });
+    };
+
+    // Ensure sender is creator
+    if sender != &listing.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Ensure no Claimant
+    if listing.claimant.is_some() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Ensure finalized & not expired, expired Listings use Relist
+    if listing.status != Status::FinalizedReady {
+        return Err(ContractError::NotPurchasable {});
+    }
+    if listing.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+
+    // Error if expiration is outside the configured bounds
+    CONFIG.load(deps.storage)?.limits.expiration.check(&expiration, &env.block)?;
+
+    let is_later = match (listing.expiration, expiration) {
+        (Some(Expiration::AtTime(old)), Expiration::AtTime(new)) => new > old,
+        (Some(Expiration::AtHeight(old)), Expiration::AtHeight(new)) => new > old,
+        (Some(Expiration::AtTime(_) | Expiration::AtHeight(_)), Expiration::Never {}) => true,
+        _ => false,
+    };
+    if !is_later {
+        return Err(ContractError::InvalidExpiration {});
+    }
+
+    listingz().replace(
+        deps.storage,
+        listing_id,
+        Some(&Listing {
+            expiration: Some(expiration),
+            ..listing.clone()
+        }),
+        Some(&listing),
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "extend_expiration")
+        .add_attribute("listing_id", listing_id.to_string())
+        .add_attribute("expiration", expiration.to_string()))
 }
 
 /// Deletes a Listing that is either **BeingPrepared** or **Expired**,
@@ -559,7 +1006,7 @@ pub fn execute_delete_listing(
     listing_id: u64,
 ) -> Result<Response, ContractError> {
     // Check listing exists, sender is owner & get listing
-    let Some(listing) = listingz().may_load(deps.storage, (&sender, listing_id))? else {
+    let Some(listing) = listingz().may_load(deps.storage, listing_id)? else {
         return Err(ContractError::NotFound {
             typ: "Listing".to_string(),
             id: listing_id.to_string(),
@@ -577,10 +1024,10 @@ pub fn execute_delete_listing(
     }
 
     // Listing can only be removed if there is no expiration (meaning it's not finalized), or it's expired
-    if let Some(exp) = listing.expiration_time {
-        if env.block.time < exp {
+    if let Some(exp) = listing.expiration {
+        if !exp.is_expired(&env.block) {
             return Err(ContractError::NotExpired {
-                x: exp.seconds().to_string(),
+                x: exp.to_string(),
             });
         }
     }
@@ -588,111 +1035,433 @@ pub fn execute_delete_listing(
     // Delete listing & send funds back to user
     let msgs = send_tokens_cosmos(&listing.creator, &listing.for_sale)?;
 
-    listingz().remove(deps.storage, (&sender, listing_id))?;
+    listingz().remove(deps.storage, listing_id)?;
+    update_stats(deps.storage, |s| s.listings.remove(&listing.status))?;
 
     Ok(Response::new().add_attribute("Remove listing", listing_id.to_string()).add_messages(msgs))
 }
 
+/// Removes a `FinalizedReady` Listing before it expires & sends `for_sale` back to the creator
+///
+/// - Listing must have been live for `Limits.min_cancel_seconds`
+/// - If `Limits.cancel_fee`, the market fee is taken from `for_sale`
+///
+/// Like purchase fees, the cancel fee is only taken from native fee denoms in `for_sale`,
+/// so Listings selling only cw20s & NFTs are cancelled for free
+pub fn execute_cancel_listing(
+    deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    listing_id: u64,
+) -> Result<Response, ContractError> {
+    // Check listing exists, sender is owner & get listing
+    let Some(listing) = listingz().may_load(deps.storage, listing_id)? else {
+        return Err(ContractError::NotFound {
+            typ: "Listing".to_string(),
+            id: listing_id.to_string(),
+        });
+    };
+
+    // Check that sender is listing creator
+    if sender != &listing.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // If listing.claimant.is_some() then listing already purchased
+    if listing.claimant.is_some() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Listings being prepared or expired can be removed with DeleteListing
+    if listing.status != Status::FinalizedReady {
+        return Err(ContractError::NotPurchasable {});
+    }
+    if listing.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+
+    let Config {
+        limits,
+        fee_recipient,
+        ..
+    } = CONFIG.load(deps.storage)?;
+
+    // Check listing has been live long enough
+    let Some(finalized_time) = listing.finalized_time else {
+        return Err(ContractError::NotPurchasable {});
+    };
+    // Saturates so a huge `min_cancel_seconds` means never rather than panicking
+    let cancel_at = finalized_time.seconds().saturating_add(limits.min_cancel_seconds);
+    if env.block.time.seconds() < cancel_at {
+        return Err(ContractError::CancelTooEarly {
+            at: cancel_at.to_string(),
+        });
+    }
+
+    // Send for_sale back to creator, less the fee if charged
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    let refund = if limits.cancel_fee {
+        let fee_config: FeeConfig = FEE_CONFIG.load(deps.storage)?;
+        let (fee_coins, balance) = calc_fee_coins(&fee_config, &listing.for_sale)?;
+        if !fee_coins.is_empty() {
+            msgs.push(fee_recipient.fee_msg(&fee_coins, &env.contract.address)?);
+            add_fee_totals(deps.storage, &fee_coins)?;
+        }
+        balance
+    } else {
+        listing.for_sale.clone()
+    };
+    msgs.extend(send_tokens_cosmos(&listing.creator, &refund)?);
+
+    listingz().remove(deps.storage, listing_id)?;
+    update_stats(deps.storage, |s| s.listings.remove(&Status::FinalizedReady))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_listing")
+        .add_attribute("listing_id", listing_id.to_string())
+        .add_event(
+            Event::new("listing_cancelled")
+                .add_attribute("listing_id", listing_id.to_string())
+                .add_attribute("creator", listing.creator.to_string()),
+        )
+        .add_messages(msgs))
+}
+
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // Purchasing
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+/// Swaps a Bucket for a Listing, the Bucket must hold at least the ask at `ask_index`
+///
+/// If `settle`, both sides are paid out in this tx & the Listing is removed.
+/// Otherwise the buyer becomes the Listing's claimant & the seller the Bucket's, to be withdrawn later
+///
+/// Anything in the Bucket beyond the ask stays with the buyer, in the same Bucket if settled,
+/// or in a new Bucket otherwise
 pub fn execute_buy_listing(
     deps: DepsMut,
     env: &Env,
     buyer: &Addr,
     listing_id: u64,
     bucket_id: u64,
+    ask_index: u32,
+    settle: bool,
 ) -> Result<Response, ContractError> {
     // Get bucket (will error if no bucket found)
-    let the_bucket = match BUCKETS.load(deps.storage, (buyer.clone(), bucket_id)) {
+    let the_bucket = match bucketz().load(deps.storage, bucket_id) {
         Ok(buck) => buck,
         Err(_) => return Err(ContractError::LoadBucketError {}),
     };
 
     // Check listing exists & get the_listing
-    let Some((_pk, the_listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id)? else {
+    let Some(the_listing) = listingz().may_load(deps.storage, listing_id)? else {
         return Err(ContractError::NotFound { typ: "Listing".to_string(), id: listing_id.to_string() });
     };
 
-    // Check that sender is bucket owner (redundant check)
-    if buyer != &the_bucket.owner {
+    // Check that sender is bucket owner
+    if buyer != the_bucket.holder() {
         return Err(ContractError::Unauthorized {});
     }
 
+    // A Bucket that already paid for a Listing holds the seller's proceeds
+    if the_bucket.is_committed() {
+        return Err(ContractError::BucketCommitted {
+            id: bucket_id,
+        });
+    }
+
     // Check that bucket contains required purchase price
-    genbal_cmp(&the_bucket.funds, &the_listing.ask)?;
+    let ask = the_listing.ask(ask_index)?.clone();
+    let surplus = genbal_contains(&the_bucket.funds, &ask)?;
 
-    // Check that listing is ready for purchase
-    if the_listing.status != Status::FinalizedReady {
-        return Err(ContractError::NotPurchasable {});
-    }
+    // Check that buyer can purchase the listing
+    check_can_buy(deps.as_ref(), &the_listing, buyer, env)?;
 
-    // Check that the user buying is whitelisted
-    if !the_listing.whitelisted_buyer.clone().map_or(true, |wl| wl == buyer.clone()) {
-        return Err(ContractError::Unauthorized {});
-    }
+    // Kept after both sides are withdrawn
+    let trade_id = record_trade(deps.storage, env, &the_listing, Some(bucket_id), buyer, &ask)?;
 
-    // Check that there's no existing claimant on listing
-    if the_listing.claimant.is_some() {
-        return Err(ContractError::NotPurchasable {});
-    }
+    // Pay out both sides now, only the surplus is left in escrow
+    if settle {
+        // Paying out is a withdraw, so it's blocked along with them
+        CONFIG.load(deps.storage)?.pause.check(&PauseAction::Withdraw)?;
 
-    // Check that listing isn't expired
-    if let Some(exp) = the_listing.expiration_time {
-        if env.block.time > exp {
-            return Err(ContractError::Expired {});
+        listingz().remove(deps.storage, listing_id)?;
+        update_stats(deps.storage, |s| s.listings.remove(&Status::FinalizedReady))?;
+        if surplus.is_empty() {
+            bucketz().remove(deps.storage, bucket_id)?;
+        } else {
+            bucketz().save(
+                deps.storage,
+                bucket_id,
+                &Bucket {
+                    funds: surplus,
+                    ..the_bucket
+                },
+            )?;
         }
+
+        let msgs = settlement_msgs(deps.as_ref(), env, &the_listing, buyer, &ask)?;
+
+        return Ok(Response::new()
+            .add_attribute("action", "buy_listing")
+            .add_attribute("bucket_used", bucket_id.to_string())
+            .add_attribute("listing_purchased:", listing_id.to_string())
+            .add_attribute("trade_id", trade_id.to_string())
+            .add_attribute("settled", "true")
+            .add_messages(msgs));
     }
 
-    // Load current fee denom
-    let fee_denom: FeeDenom = FEE_DENOM.load(deps.storage)?;
+    // Load current fee schedule
+    let fee_config: FeeConfig = FEE_CONFIG.load(deps.storage)?;
 
     // Calculate Fee amount for Listing (paid by Listing Buyer on withdraw)
-    let (l_fee_coin, l_balance) = calc_fee_coin(&fee_denom, &the_listing.for_sale)?;
+    let (l_fee_coins, l_balance) = calc_fee_coins(&fee_config, &the_listing.for_sale)?;
 
-    // Delete Old Listing -> Save new listing with listing_buyer in key / creator && Fee
-    listingz().remove(deps.storage, (&the_listing.creator, listing_id))?;
+    // Listing stays under its id with the buyer as claimant && Fee
     listingz().save(
         deps.storage,
-        (buyer, listing_id),
+        listing_id,
         &Listing {
-            creator: buyer.clone(),
             claimant: Some(buyer.clone()),
             status: Status::Closed,
-            fee_amount: l_fee_coin,
+            fee_amount: l_fee_coins,
             for_sale: l_balance,
+            creator: the_listing.creator.clone(),
             ..the_listing
         },
     )?;
+    update_stats(deps.storage, |s| s.listings.moved(&Status::FinalizedReady, &Status::Closed))?;
 
     // Calculate Fee amount for Bucket (paid by Listing Seller on withdraw)
-    let (b_fee_coin, b_balance) = calc_fee_coin(&fee_denom, &the_bucket.funds)?;
+    let (b_fee_coins, b_balance) = calc_fee_coins(&fee_config, &ask)?;
 
-    // Delete Old Bucket -> Save new Bucket with listing_seller in key / owner && Fee
-    BUCKETS.remove(deps.storage, (buyer.clone(), bucket_id));
-    BUCKETS.save(
+    // Bucket stays under its id with the seller as claimant && Fee
+    bucketz().save(
         deps.storage,
-        (the_listing.creator.clone(), bucket_id),
+        bucket_id,
         &Bucket {
-            owner: the_listing.creator,
             funds: b_balance,
-            fee_amount: b_fee_coin,
+            fee_amount: b_fee_coins,
+            paid_for: Some(listing_id),
+            claimant: Some(the_listing.creator),
+            ..the_bucket
         },
     )?;
 
-    Ok(Response::new()
+    let res = Response::new()
         .add_attribute("action", "buy_listing")
         .add_attribute("bucket_used", bucket_id.to_string())
-        .add_attribute("listing_purchased:", listing_id.to_string()))
+        .add_attribute("listing_purchased:", listing_id.to_string())
+        .add_attribute("trade_id", trade_id.to_string());
+
+    if surplus.is_empty() {
+        return Ok(res);
+    }
+
+    // Surplus goes back to the buyer in a new Bucket
+    let surplus_id = BUCKET_COUNT.load(deps.storage)?;
+    if bucketz().has(deps.storage, surplus_id) {
+        return Err(ContractError::IdAlreadyExists {});
+    }
+    bucketz().save(
+        deps.storage,
+        surplus_id,
+        &Bucket {
+            owner: buyer.clone(),
+            funds: surplus,
+            fee_amount: vec![],
+            paid_for: None,
+            claimant: None,
+        },
+    )?;
+    BUCKET_COUNT
+        .update(deps.storage, |old| -> Result<u64, StdError> {
+            Ok(old.checked_add(1).unwrap_or(1))
+        })
+        .map_err(|_| ContractError::GenericError("Error updating Bucket Count".to_string()))?;
+
+    Ok(res.add_attribute("surplus_bucket", surplus_id.to_string()))
+}
+
+/// Buys a Listing with `payment` sent in this tx, no Bucket needed
+///
+/// `payment` must match the ask at `ask_index`, both sides are paid out in the response
+pub fn execute_buy_listing_with_funds(
+    deps: DepsMut,
+    env: &Env,
+    buyer: &Addr,
+    listing_id: u64,
+    ask_index: u32,
+    payment: &Balance,
+) -> Result<Response, ContractError> {
+    // Error if payment contains duplicates or 0 balances
+    payment.normalized_check()?;
+
+    // Check listing exists & get the_listing
+    let Some(the_listing) = listingz().may_load(deps.storage, listing_id)? else {
+        return Err(ContractError::NotFound {
+            typ: "Listing".to_string(),
+            id: listing_id.to_string(),
+        });
+    };
+
+    // Check that payment is the asking price
+    let payment = GenericBalance::from_balance(payment);
+    genbal_cmp(&payment, the_listing.ask(ask_index)?)?;
+
+    // Check that buyer can purchase the listing
+    check_can_buy(deps.as_ref(), &the_listing, buyer, env)?;
+
+    // Both sides are paid out here, which is a withdraw
+    CONFIG.load(deps.storage)?.pause.check(&PauseAction::Withdraw)?;
+
+    let trade_id = record_trade(deps.storage, env, &the_listing, None, buyer, &payment)?;
+
+    // Nothing left in escrow for this listing
+    listingz().remove(deps.storage, listing_id)?;
+    update_stats(deps.storage, |s| s.listings.remove(&Status::FinalizedReady))?;
+
+    let msgs = settlement_msgs(deps.as_ref(), env, &the_listing, buyer, &payment)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "buy_listing_with_funds")
+        .add_attribute("listing_purchased", listing_id.to_string())
+        .add_attribute("trade_id", trade_id.to_string())
+        .add_attribute("buyer", buyer.to_string())
+        .add_messages(msgs))
+}
+
+/// Errors if `buyer` can't purchase `listing` right now
+fn check_can_buy(
+    deps: Deps,
+    listing: &Listing,
+    buyer: &Addr,
+    env: &Env,
+) -> Result<(), ContractError> {
+    // Check that listing is ready for purchase
+    if listing.status != Status::FinalizedReady {
+        return Err(ContractError::NotPurchasable {});
+    }
+
+    // Check that the user buying meets the buyer restriction
+    if let Some(restriction) = &listing.buyer_restriction {
+        restriction.check(&deps.querier, buyer)?;
+    }
+
+    // Check that there's no existing claimant on listing
+    if listing.claimant.is_some() {
+        return Err(ContractError::NotPurchasable {});
+    }
+
+    // Check that listing has started
+    if let Some(start) = listing.start.filter(|_| !listing.has_started(&env.block)) {
+        return Err(ContractError::NotStarted {
+            start: start.to_string(),
+        });
+    }
+
+    // Check that listing isn't expired
+    if listing.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+
+    Ok(())
+}
+
+/// Messages paying out a purchase immediately
+/// - `listing.for_sale` minus fees to `buyer`
+/// - `payment` minus fees to `listing.creator`
+/// - Fees from both sides to `Config.fee_recipient`
+fn settlement_msgs(
+    deps: Deps,
+    env: &Env,
+    listing: &Listing,
+    buyer: &Addr,
+    payment: &GenericBalance,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let fee_config = FEE_CONFIG.load(deps.storage)?;
+    let fee_recipient = CONFIG.load(deps.storage)?.fee_recipient;
+
+    let (l_fee_coins, l_balance) = calc_fee_coins(&fee_config, &listing.for_sale)?;
+    let (b_fee_coins, b_balance) = calc_fee_coins(&fee_config, payment)?;
+
+    let mut msgs = send_tokens_cosmos(buyer, &l_balance)?;
+    msgs.append(&mut send_tokens_cosmos(&listing.creator, &b_balance)?);
+
+    for fees in [l_fee_coins, b_fee_coins] {
+        if !fees.is_empty() {
+            msgs.push(fee_recipient.fee_msg(&fees, &env.contract.address)?);
+        }
+    }
+
+    Ok(msgs)
+}
+
+/// Saves a `Trade` for `listing` being bought by `buyer` with `paid`, returns its id
+///
+/// `listing` must still hold `for_sale` before fees
+fn record_trade(
+    storage: &mut dyn Storage,
+    env: &Env,
+    listing: &Listing,
+    bucket_id: Option<u64>,
+    buyer: &Addr,
+    paid: &GenericBalance,
+) -> Result<u64, ContractError> {
+    let fee_config = FEE_CONFIG.load(storage)?;
+    let (listing_fee, _) = calc_fee_coins(&fee_config, &listing.for_sale)?;
+    let (ask_fee, _) = calc_fee_coins(&fee_config, paid)?;
+
+    let id = TRADE_COUNT.load(storage)?;
+    let trade = Trade {
+        id,
+        listing_id: listing.id,
+        bucket_id,
+        seller: listing.creator.clone(),
+        buyer: buyer.clone(),
+        for_sale: listing.for_sale.clone(),
+        paid: paid.clone(),
+        listing_fee,
+        ask_fee,
+        time: env.block.time,
+        height: env.block.height,
+    };
+
+    if trades().has(storage, id) {
+        return Err(ContractError::IdAlreadyExists {});
+    }
+    trades().save(storage, id, &trade)?;
+    for collection in trade.collections() {
+        TRADE_COLLECTIONS.save(storage, (collection, id), &Empty {})?;
+    }
+    update_stats(storage, |s| s.trades = s.trades.saturating_add(1))?;
+    add_trade_totals(storage, &trade)?;
+
+    TRADE_COUNT
+        .update(storage, |old| -> Result<u64, StdError> { Ok(old.checked_add(1).unwrap_or(1)) })
+        .map_err(|_| ContractError::GenericError("Error updating Trade Count".to_string()))?;
+
+    Ok(id)
+}
+
+/// Applies `f` to the stored `Stats`
+fn update_stats(
+    storage: &mut dyn Storage,
+    f: impl FnOnce(&mut Stats),
+) -> Result<(), ContractError> {
+    let mut stats = STATS.load(storage)?;
+    f(&mut stats);
+    STATS.save(storage, &stats)?;
+    Ok(())
 }
 
 pub fn execute_withdraw_purchased(
     deps: DepsMut,
+    env: &Env,
     withdrawer: &Addr,
     listing_id: u64,
 ) -> Result<Response, ContractError> {
     // Get listing
-    let Some((_pk, the_listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id)? else {
+    let Some(the_listing) = listingz().may_load(deps.storage, listing_id)? else {
         return Err(ContractError::NotFound { typ: "Listing".to_string(), id: listing_id.to_string() });
     };
 
@@ -710,9 +1479,11 @@ pub fn execute_withdraw_purchased(
     };
 
     // Delete Listing
-    listingz().remove(deps.storage, (&listing_claimant, listing_id))?;
+    listingz().remove(deps.storage, listing_id)?;
+    update_stats(deps.storage, |s| s.listings.remove(&Status::Closed))?;
 
-    let withdraw_msgs = the_listing.withdraw_msgs()?;
+    let fee_recipient = CONFIG.load(deps.storage)?.fee_recipient;
+    let withdraw_msgs = the_listing.withdraw_msgs(&env.contract.address, &fee_recipient)?;
 
     Ok(Response::new()
         .add_attribute("Action", "withdraw_purchased")
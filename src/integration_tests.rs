@@ -3,11 +3,15 @@
 pub use self::create_contract::*;
 pub use self::create_users::*;
 pub use self::init_contracts::init_all_contracts;
+pub use self::stargate_app::App;
 use crate::integration_tests_imports::*;
-pub use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+pub use cw_multi_test::{Contract, ContractWrapper, Executor};
 
 const VALID_NATIVE: &str = "ujunox";
 
+// Stands in for the distribution module account in cw-multi-test
+pub const COMMUNITY_POOL: &str = "community_pool";
+
 pub fn here(ctx: impl Display, line: impl Display, col: impl Display) -> String {
     format!(
         "~~~~~~~~~~~~~~~~~~~ \n \n {} \n line {} | column {} \n ________________________",
@@ -15,8 +19,216 @@ pub fn here(ctx: impl Display, line: impl Display, col: impl Display) -> String
     )
 }
 
+/// `Expiration::AtTime` `seconds` after the current block
+pub fn expires_in(router: &App, seconds: u64) -> Expiration {
+    Expiration::AtTime(router.block_info().time.plus_seconds(seconds))
+}
+
+/// cw-multi-test 0.16 has no Stargate module, its router errors on every `CosmosMsg::Stargate`
+///
+/// `App` runs contracts through `StargateWasm`, which hands them a router that executes
+/// `MsgFundCommunityPool` as a transfer from the depositor to `COMMUNITY_POOL`
+pub mod stargate_app {
+    use super::COMMUNITY_POOL;
+    use crate::state::{MsgFundCommunityPool, FUND_COMMUNITY_POOL_TYPE_URL};
+    use anyhow::{bail, Result as AnyResult};
+    use cosmwasm_std::testing::{MockApi, MockStorage};
+    use cosmwasm_std::{
+        coin, Addr, Api, BankMsg, Binary, BlockInfo, CosmosMsg, Empty, Querier, QueryRequest,
+        Storage, WasmMsg, WasmQuery,
+    };
+    use cw_multi_test::{
+        AppBuilder, AppResponse, BankKeeper, Contract, CosmosRouter, Executor, FailingModule,
+        SudoMsg, Wasm, WasmKeeper,
+    };
+    use std::cell::RefCell;
+    use std::ops::{Deref, DerefMut};
+    use std::rc::Rc;
+
+    type BaseApp = cw_multi_test::App<
+        BankKeeper,
+        MockApi,
+        MockStorage,
+        FailingModule<Empty, Empty, Empty>,
+        StargateWasm,
+    >;
+
+    /// `cw_multi_test::App` with `StargateWasm` in place of `WasmKeeper`
+    ///
+    /// Derefs to the inner App, `store_code` goes to the `WasmKeeper` it shares with `StargateWasm`
+    pub struct App {
+        app: BaseApp,
+        wasm: Rc<RefCell<WasmKeeper<Empty, Empty>>>,
+    }
+
+    impl Default for App {
+        fn default() -> Self {
+            let wasm = Rc::new(RefCell::new(WasmKeeper::new()));
+            let app = AppBuilder::new()
+                .with_wasm::<FailingModule<Empty, Empty, Empty>, _>(StargateWasm {
+                    inner: wasm.clone(),
+                })
+                .build(|_, _, _| {});
+
+            App {
+                app,
+                wasm,
+            }
+        }
+    }
+
+    impl App {
+        pub fn store_code(&mut self, code: Box<dyn Contract<Empty>>) -> u64 {
+            self.wasm.borrow_mut().store_code(code) as u64
+        }
+    }
+
+    // Implemented on `App` itself so `execute_contract(.., expires_in(router, ..))` still borrows
+    impl Executor<Empty> for App {
+        fn execute(&mut self, sender: Addr, msg: CosmosMsg) -> AnyResult<AppResponse> {
+            self.app.execute(sender, msg)
+        }
+    }
+
+    impl Deref for App {
+        type Target = BaseApp;
+
+        fn deref(&self) -> &BaseApp {
+            &self.app
+        }
+    }
+
+    impl DerefMut for App {
+        fn deref_mut(&mut self) -> &mut BaseApp {
+            &mut self.app
+        }
+    }
+
+    /// `WasmKeeper` whose contracts send their messages through `StargateRouter`
+    pub struct StargateWasm {
+        inner: Rc<RefCell<WasmKeeper<Empty, Empty>>>,
+    }
+
+    impl Wasm<Empty, Empty> for StargateWasm {
+        fn query(
+            &self,
+            api: &dyn Api,
+            storage: &dyn Storage,
+            querier: &dyn Querier,
+            block: &BlockInfo,
+            request: WasmQuery,
+        ) -> AnyResult<Binary> {
+            self.inner.borrow().query(api, storage, querier, block, request)
+        }
+
+        fn execute(
+            &self,
+            api: &dyn Api,
+            storage: &mut dyn Storage,
+            router: &dyn CosmosRouter<ExecC = Empty, QueryC = Empty>,
+            block: &BlockInfo,
+            sender: Addr,
+            msg: WasmMsg,
+        ) -> AnyResult<AppResponse> {
+            self.inner.borrow().execute(api, storage, &StargateRouter(router), block, sender, msg)
+        }
+
+        fn sudo(
+            &self,
+            api: &dyn Api,
+            contract_addr: Addr,
+            storage: &mut dyn Storage,
+            router: &dyn CosmosRouter<ExecC = Empty, QueryC = Empty>,
+            block: &BlockInfo,
+            msg: Binary,
+        ) -> AnyResult<AppResponse> {
+            self.inner.borrow().sudo(
+                api,
+                contract_addr,
+                storage,
+                &StargateRouter(router),
+                block,
+                msg,
+            )
+        }
+    }
+
+    /// Executes `MsgFundCommunityPool`, everything else goes to the App's router
+    struct StargateRouter<'a>(&'a dyn CosmosRouter<ExecC = Empty, QueryC = Empty>);
+
+    impl CosmosRouter for StargateRouter<'_> {
+        type ExecC = Empty;
+        type QueryC = Empty;
+
+        fn execute(
+            &self,
+            api: &dyn Api,
+            storage: &mut dyn Storage,
+            block: &BlockInfo,
+            sender: Addr,
+            msg: CosmosMsg,
+        ) -> AnyResult<AppResponse> {
+            let CosmosMsg::Stargate {
+                type_url,
+                value,
+            } = msg
+            else {
+                return self.0.execute(api, storage, block, sender, msg);
+            };
+
+            if type_url != FUND_COMMUNITY_POOL_TYPE_URL {
+                bail!("Unsupported Stargate message {type_url}");
+            }
+
+            let fund: MsgFundCommunityPool = prost::Message::decode(value.as_slice())?;
+            if fund.depositor != sender.as_str() {
+                bail!("Depositor {} didn't send MsgFundCommunityPool", fund.depositor);
+            }
+
+            let amount = fund
+                .amount
+                .iter()
+                .map(|c| Ok(coin(c.amount.parse()?, &c.denom)))
+                .collect::<AnyResult<Vec<_>>>()?;
+
+            self.0.execute(
+                api,
+                storage,
+                block,
+                sender,
+                BankMsg::Send {
+                    to_address: COMMUNITY_POOL.to_string(),
+                    amount,
+                }
+                .into(),
+            )
+        }
+
+        fn query(
+            &self,
+            api: &dyn Api,
+            storage: &dyn Storage,
+            block: &BlockInfo,
+            request: QueryRequest<Empty>,
+        ) -> AnyResult<Binary> {
+            self.0.query(api, storage, block, request)
+        }
+
+        fn sudo(
+            &self,
+            api: &dyn Api,
+            storage: &mut dyn Storage,
+            block: &BlockInfo,
+            msg: SudoMsg,
+        ) -> AnyResult<AppResponse> {
+            self.0.sudo(api, storage, block, msg)
+        }
+    }
+}
+
 pub mod create_contract {
     use crate::integration_tests::{Contract, ContractWrapper, Empty};
+    use cosmwasm_std::{coin, DepsMut, Env, MessageInfo, Response};
 
     pub fn cw20_contract() -> Box<dyn Contract<Empty>> {
         let contract = ContractWrapper::new(
@@ -41,16 +253,166 @@ pub mod create_contract {
             crate::contract::execute,
             crate::contract::instantiate,
             crate::contract::query,
-        );
+        )
+        .with_migrate(crate::contract::migrate);
 
         Box::new(contract)
     }
+
+    #[cosmwasm_schema::cw_serde]
+    pub struct LegacyInstantiateMsg {
+        pub contract: String,
+        pub version: String,
+    }
+
+    /// Stand-in for a deployed v0.2.0 market
+    ///
+    /// Instantiating writes the v0.2.0 storage layout (including Listing index entries)
+    /// - Listing 1 | sold by john, bought by sam, 5_000 JUNO fee
+    /// - Listing 2 | max | being prepared
+    /// - Bucket 1 | john | 2_000 JUNO proceeds of Listing 1, 10 JUNO fee
+    /// - Bucket 2 | sam | 300 JUNO
+    ///
+    /// Funds sent on instantiate should cover 1_000_000 + 500 + 2_000 + 300 JUNO
+    pub fn fuzionmarket_v0_2_0_contract() -> Box<dyn Contract<Empty>> {
+        use crate::migrate::v0_2_0;
+        use crate::state::{GenericBalance, Status, BUCKET_COUNT, LISTING_COUNT};
+        use cosmwasm_std::{coins, Addr, Binary, Deps, StdError, StdResult};
+
+        fn instantiate(
+            deps: DepsMut,
+            _env: Env,
+            _info: MessageInfo,
+            msg: LegacyInstantiateMsg,
+        ) -> StdResult<Response> {
+            cw2::set_contract_version(deps.storage, msg.contract, msg.version)?;
+            LISTING_COUNT.save(deps.storage, &3)?;
+            BUCKET_COUNT.save(deps.storage, &3)?;
+            v0_2_0::FEE_DENOM.save(deps.storage, &v0_2_0::FeeDenom::JUNO)?;
+
+            let (john, sam, max) =
+                (Addr::unchecked("john"), Addr::unchecked("sam"), Addr::unchecked("max"));
+            let juno = |amount: u128| GenericBalance {
+                native: coins(amount, super::VALID_NATIVE),
+                cw20: vec![],
+                nfts: vec![],
+            };
+
+            // v0.2.0 made the buyer the creator of a purchased Listing
+            let listings = [
+                v0_2_0::Listing {
+                    creator: sam.clone(),
+                    id: 1,
+                    finalized_time: None,
+                    expiration_time: None,
+                    status: Status::Closed,
+                    claimant: Some(sam.clone()),
+                    whitelisted_buyer: None,
+                    for_sale: juno(995_000),
+                    ask: juno(2_000),
+                    fee_amount: Some(coin(5_000, super::VALID_NATIVE)),
+                },
+                v0_2_0::Listing {
+                    creator: max.clone(),
+                    id: 2,
+                    finalized_time: None,
+                    expiration_time: None,
+                    status: Status::BeingPrepared,
+                    claimant: None,
+                    whitelisted_buyer: None,
+                    for_sale: juno(500),
+                    ask: juno(100),
+                    fee_amount: None,
+                },
+            ];
+
+            for old in listings {
+                v0_2_0::listingz().save(deps.storage, (&old.creator, old.id), &old)?;
+            }
+
+            v0_2_0::BUCKETS.save(
+                deps.storage,
+                (john.clone(), 1),
+                &v0_2_0::Bucket {
+                    owner: john,
+                    funds: juno(1_990),
+                    fee_amount: Some(coin(10, super::VALID_NATIVE)),
+                },
+            )?;
+            v0_2_0::BUCKETS.save(
+                deps.storage,
+                (sam.clone(), 2),
+                &v0_2_0::Bucket {
+                    owner: sam,
+                    funds: juno(300),
+                    fee_amount: None,
+                },
+            )?;
+
+            Ok(Response::new())
+        }
+
+        fn execute(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+            Err(StdError::generic_err("v0.2.0 stand-in"))
+        }
+
+        fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+            Err(StdError::generic_err("v0.2.0 stand-in"))
+        }
+
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+
+    #[cosmwasm_schema::cw_serde]
+    pub struct Cw4GroupInstantiateMsg {
+        pub members: Vec<String>,
+    }
+
+    /// Stand-in for a cw4 group, only answers `Member` queries
+    ///
+    /// Every member has a weight of 1
+    pub fn cw4_group_contract() -> Box<dyn Contract<Empty>> {
+        use crate::state::{Cw4MemberResponse, Cw4QueryMsg};
+        use cosmwasm_std::{to_binary, Binary, Deps, StdError, StdResult};
+        use cw_storage_plus::Map;
+
+        const MEMBERS: Map<&str, u64> = Map::new("members");
+
+        fn instantiate(
+            deps: DepsMut,
+            _env: Env,
+            _info: MessageInfo,
+            msg: Cw4GroupInstantiateMsg,
+        ) -> StdResult<Response> {
+            for member in msg.members {
+                MEMBERS.save(deps.storage, &member, &1)?;
+            }
+            Ok(Response::new())
+        }
+
+        fn execute(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+            Err(StdError::generic_err("cw4 stand-in"))
+        }
+
+        fn query(deps: Deps, _: Env, msg: Cw4QueryMsg) -> StdResult<Binary> {
+            match msg {
+                Cw4QueryMsg::Member {
+                    addr,
+                    ..
+                } => to_binary(&Cw4MemberResponse {
+                    weight: MEMBERS.may_load(deps.storage, &addr)?,
+                }),
+            }
+        }
+
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
 }
 
 pub mod create_users {
+    use super::App;
     use super::VALID_NATIVE;
     use cosmwasm_std::Addr;
-    use cw_multi_test::App;
     use std::borrow::BorrowMut;
 
     pub struct User {
@@ -145,7 +507,19 @@ pub mod init_contracts {
 
     pub fn init_jv_contract(router: &mut App, admin: &Addr) -> Addr {
         let jv_id = router.store_code(fuzionmarket_contract());
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            admin: None,
+            fee_recipient: None,
+            fee_config: FeeConfig {
+                fee_denoms: vec![FeeDenom {
+                    denom: VALID_NATIVE.to_string(),
+                    bps: 50,
+                    min_fee: None,
+                }],
+            },
+            limits: None,
+            token_lists: None,
+        };
 
         let addr =
             router.instantiate_contract(jv_id, admin.clone(), &msg, &[], "jv", None).unwrap();
@@ -155,6 +529,7 @@ pub mod init_contracts {
         addr
     }
 
+    #[allow(clippy::type_complexity)]
     pub fn init_all_contracts(
         router: &mut App,
         contract_admin: &User,
@@ -337,7 +712,9 @@ pub mod create_valid_listing {
     use cosmwasm_std::{coin, Addr, Uint128}; //coins
     use cw20::Cw20CoinVerified; //, Cw20Coin};
 
-    use crate::{msg::CreateListingMsg, state::GenericBalance, state::Nft};
+    use crate::{
+        msg::CreateListingMsg, state::BuyerRestriction, state::GenericBalance, state::Nft,
+    };
 
     use super::VALID_NATIVE; // REAL_JVONE, REAL_NEONPEEPZ};
 
@@ -368,9 +745,9 @@ pub mod create_valid_listing {
 
         let cm = CreateListingMsg {
             //id: 11,
-            ask: valid_ask_price,
+            asks: vec![valid_ask_price],
             //whitelisted_purchasers: None,
-            whitelisted_buyer: None,
+            buyer_restriction: None,
         };
 
         crate::msg::ExecuteMsg::CreateListing {
@@ -378,6 +755,7 @@ pub mod create_valid_listing {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn create_valid_ask(
         //listing_id: u64,
         juno_amt: Option<u128>,
@@ -396,7 +774,7 @@ pub mod create_valid_listing {
         sk_id: Option<String>,
 
         //whitelisted_purchasers: Option<Vec<String>>,
-        whitelisted_buyer: Option<String>,
+        buyer_restriction: Option<BuyerRestriction>,
     ) -> ExecuteMsg {
         let native_ask = match juno_amt {
             None => vec![],
@@ -450,9 +828,9 @@ pub mod create_valid_listing {
 
         let cm = CreateListingMsg {
             //id: listing_id,
-            ask: valid_ask_price,
+            asks: vec![valid_ask_price],
             //whitelisted_purchasers: whitelisted_purchasers,
-            whitelisted_buyer,
+            buyer_restriction,
         };
 
         crate::msg::ExecuteMsg::CreateListing {
@@ -465,7 +843,7 @@ pub mod create_valid_listing {
         jvone_addr: Addr,
         np_addr: Addr,
         //whitelist: Option<Vec<Addr>>,
-        whitelisted_buyer: Option<String>,
+        buyer_restriction: Option<BuyerRestriction>,
     ) -> CreateListingMsg {
         let native_ask = cosmwasm_std::coins(1, "ujunox");
 
@@ -490,9 +868,9 @@ pub mod create_valid_listing {
 
         CreateListingMsg {
             //id: listing_id,
-            ask: ask_price,
+            asks: vec![ask_price],
             //whitelisted_purchasers: whitelist,
-            whitelisted_buyer,
+            buyer_restriction,
         }
     }
 }
@@ -1235,6 +1613,8 @@ fn remove_a_listing() -> Result<(), anyhow::Error> {
 
     let q = crate::msg::QueryMsg::GetListingsByOwner {
         owner: john.address.clone().to_string(),
+        start_after: None,
+        limit: None,
     };
 
     let res: crate::query::MultiListingResponse =
@@ -1483,7 +1863,8 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
 
     let finalize_john_1 = crate::msg::ExecuteMsg::Finalize {
         listing_id: 1,
-        seconds: 259200,
+        expiration: expires_in(router, 259200),
+        start: None,
     };
 
     let res: Result<AppResponse> =
@@ -1499,7 +1880,8 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
 
     let too_early = crate::msg::ExecuteMsg::Finalize {
         listing_id: 1,
-        seconds: 599,
+        expiration: expires_in(router, 599),
+        start: None,
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), fuzionmarket.clone(), &too_early, &[]);
@@ -1507,7 +1889,8 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
 
     let too_late = crate::msg::ExecuteMsg::Finalize {
         listing_id: 1,
-        seconds: 1209601,
+        expiration: expires_in(router, 1209601),
+        start: None,
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), fuzionmarket.clone(), &too_late, &[]);
@@ -1520,7 +1903,8 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
 
     let just_right = crate::msg::ExecuteMsg::Finalize {
         listing_id: 1,
-        seconds: 20000,
+        expiration: expires_in(router, 20000),
+        start: None,
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), fuzionmarket.clone(), &just_right, &[]);
@@ -1532,7 +1916,8 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     let finalize_again = crate::msg::ExecuteMsg::Finalize {
         listing_id: 1,
-        seconds: 20000,
+        expiration: expires_in(router, 20000),
+        start: None,
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), fuzionmarket.clone(), &finalize_again, &[]);
@@ -1753,7 +2138,8 @@ fn expiration_checks() -> Result<(), anyhow::Error> {
 
     let just_right = crate::msg::ExecuteMsg::Finalize {
         listing_id: 1,
-        seconds: 20000,
+        expiration: expires_in(router, 20000),
+        start: None,
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), fuzionmarket.clone(), &just_right, &[]);
@@ -1786,7 +2172,8 @@ fn expiration_checks() -> Result<(), anyhow::Error> {
     // Cant refinalize an expired listing
     let fail_refinalize_expired = crate::msg::ExecuteMsg::Finalize {
         listing_id: 1,
-        seconds: 15000,
+        expiration: expires_in(router, 15000),
+        start: None,
     };
     let res: Result<AppResponse> = router.execute_contract(
         john.address.clone(),
@@ -1836,6 +2223,8 @@ fn expiration_checks() -> Result<(), anyhow::Error> {
 
     let q = crate::msg::QueryMsg::GetListingsByOwner {
         owner: john.address.clone().to_string(),
+        start_after: None,
+        limit: None,
     };
     let res: crate::query::MultiListingResponse =
         router.wrap().query_wasm_smart(fuzionmarket, &q).unwrap();
@@ -2061,6 +2450,8 @@ fn create_bucket() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     let q = crate::msg::QueryMsg::GetBuckets {
         bucket_owner: john.address.clone().to_string(),
+        start_after: None,
+        limit: None,
     };
     let res: crate::query::GetBucketsResponse =
         router.wrap().query_wasm_smart(fuzionmarket, &q).unwrap();
@@ -2134,9 +2525,9 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
     };
     let cl = CreateListingMsg {
         //id: 1,
-        ask: ask_price.clone(),
+        asks: vec![ask_price.clone()],
         //whitelisted_purchasers: Some(vec![sam.address.to_string(), john.address.to_string()]),
-        whitelisted_buyer: Some(sam.address.to_string()),
+        buyer_restriction: Some(BuyerRestriction::Address(sam.address.clone())),
     };
     let clm = crate::msg::ExecuteMsg::CreateListing {
         create_msg: cl,
@@ -2200,7 +2591,8 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
 
     let finalize = crate::msg::ExecuteMsg::Finalize {
         listing_id: 1,
-        seconds: 10000,
+        expiration: expires_in(router, 10000),
+        start: None,
     };
 
     let res: Result<AppResponse> =
@@ -2256,7 +2648,9 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
     // Try to buy listing, should fail
     let buy_msg = crate::msg::ExecuteMsg::BuyListing {
         listing_id: 1,
+        ask_index: 0,
         bucket_id: 1,
+        settle: false,
     };
     let res: Result<AppResponse> =
         router.execute_contract(sam.address.clone(), fuzionmarket.clone(), &buy_msg, &[]);
@@ -2305,7 +2699,9 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
     // Try to buy listing, should fail
     let buy_msg = crate::msg::ExecuteMsg::BuyListing {
         listing_id: 1,
+        ask_index: 0,
         bucket_id: 2,
+        settle: false,
     };
     let res: Result<AppResponse> =
         router.execute_contract(sam.address.clone(), fuzionmarket.clone(), &buy_msg, &[]);
@@ -2354,7 +2750,9 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
     // Try to buy listing, should fail
     let buy_msg = crate::msg::ExecuteMsg::BuyListing {
         listing_id: 1,
+        ask_index: 0,
         bucket_id: 3,
+        settle: false,
     };
     let res: Result<AppResponse> =
         router.execute_contract(sam.address.clone(), fuzionmarket.clone(), &buy_msg, &[]);
@@ -2402,7 +2800,9 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
     // Try to buy listing, should fail
     let buy_msg = crate::msg::ExecuteMsg::BuyListing {
         listing_id: 1,
+        ask_index: 0,
         bucket_id: 4,
+        settle: false,
     };
     let res: Result<AppResponse> =
         router.execute_contract(sam.address.clone(), fuzionmarket.clone(), &buy_msg, &[]);
@@ -2436,7 +2836,9 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
     // Try to buy listing not whitelisted for, should fail
     let buy_msg = crate::msg::ExecuteMsg::BuyListing {
         listing_id: 1,
+        ask_index: 0,
         bucket_id: 5,
+        settle: false,
     };
     let res: Result<AppResponse> =
         router.execute_contract(max.address.clone(), fuzionmarket.clone(), &buy_msg, &[]);
@@ -2479,7 +2881,9 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
     // Try to buy listing, should succeed
     let buy_msg = crate::msg::ExecuteMsg::BuyListing {
         listing_id: 1,
+        ask_index: 0,
         bucket_id: 6,
+        settle: false,
     };
     let res: Result<AppResponse> =
         router.execute_contract(sam.address.clone(), fuzionmarket.clone(), &buy_msg, &[]);
@@ -2551,7 +2955,8 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     let finalize = crate::msg::ExecuteMsg::Finalize {
         listing_id: 1,
-        seconds: 10000,
+        expiration: expires_in(router, 10000),
+        start: None,
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), fuzionmarket.clone(), &finalize, &[]);
@@ -2581,7 +2986,7 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
     };
     let edit_price = crate::msg::ExecuteMsg::ChangeAsk {
         listing_id: 1,
-        new_ask: ask_price,
+        new_asks: vec![ask_price],
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), fuzionmarket.clone(), &edit_price, &[]);
@@ -2632,9 +3037,9 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
 
     // Sam balance checks
     // Sam should have
-    // 105_000_000 JUNO before 0.1% fee
-    // 0.1% of 5_000_000 is = 5_000
-    // should have 104_995_000 JUNO
+    // 105_000_000 JUNO before 0.5% fee
+    // 0.5% of 5_000_000 is = 25_000
+    // should have 104_975_000 JUNO
     // 110 JVONE
     // 80 JVTWO
     // NeonPeepz #1, #3, #4
@@ -2642,10 +3047,17 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
     let sam_juno_bal: Coin =
         router.wrap().query_balance(sam.address.to_string(), "ujunox").unwrap();
     ensure!(
-        (sam_juno_bal.amount == Uint128::from(104_995_000_u32)),
+        (sam_juno_bal.amount == Uint128::from(104_975_000_u32)),
         here("Sam juno balance wrong", line!(), column!())
     );
 
+    // Fee paid out to the Community Pool
+    let cp_juno_bal: Coin = router.wrap().query_balance(COMMUNITY_POOL, "ujunox").unwrap();
+    ensure!(
+        (cp_juno_bal.amount == Uint128::from(25_000_u32)),
+        here("Community Pool juno balance wrong", line!(), column!())
+    );
+
     assert_eq!(jvone.balance(&router.wrap(), sam.address.clone()), Ok(Uint128::from(110u32)));
 
     assert_eq!(jvtwo.balance(&router.wrap(), sam.address.clone()), Ok(Uint128::from(80u32)));
@@ -2741,9 +3153,9 @@ fn cant_buy_expired() -> Result<(), anyhow::Error> {
     };
     let cl = CreateListingMsg {
         //id: 1,
-        ask: ask_price,
+        asks: vec![ask_price],
         //whitelisted_purchasers: None,
-        whitelisted_buyer: None,
+        buyer_restriction: None,
     };
     let clm = crate::msg::ExecuteMsg::CreateListing {
         create_msg: cl,
@@ -2807,7 +3219,8 @@ fn cant_buy_expired() -> Result<(), anyhow::Error> {
 
     let finalize = crate::msg::ExecuteMsg::Finalize {
         listing_id: 1,
-        seconds: 10000,
+        expiration: expires_in(router, 10000),
+        start: None,
     };
 
     let res: Result<AppResponse> =
@@ -2866,7 +3279,9 @@ fn cant_buy_expired() -> Result<(), anyhow::Error> {
     // Try to buy listing, should fail
     let buy_msg = crate::msg::ExecuteMsg::BuyListing {
         listing_id: 1,
+        ask_index: 0,
         bucket_id: 1,
+        settle: false,
     };
     let res: Result<AppResponse> =
         router.execute_contract(sam.address.clone(), fuzionmarket, &buy_msg, &[]);
@@ -2922,9 +3337,9 @@ fn storage_capacity() -> Result<(), anyhow::Error> {
     };
     let cl = CreateListingMsg {
         //id: 1,
-        ask: ask_price,
+        asks: vec![ask_price],
         //whitelisted_purchasers: None,
-        whitelisted_buyer: None,
+        buyer_restriction: None,
     };
     let clm = crate::msg::ExecuteMsg::CreateListing {
         create_msg: cl,
@@ -2988,3 +3403,4663 @@ fn storage_capacity() -> Result<(), anyhow::Error> {
     // );
     // ensure!(res.is_ok(), here("'Testing Ask Creation' failure", line!(), column!()));
 }
+
+// <X> Only Admin can update the fee schedule
+// <X> Invalid fee schedules are rejected
+// <X> Fees are charged at the configured rate, raised to the minimum fee
+#[test]
+fn fee_config() -> Result<(), anyhow::Error> {
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, jvtwo, _jvtre, _neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Default schedule is 0.5% JUNO
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: crate::query::FeeDenomResponse =
+        router.wrap().query_wasm_smart(fuzionmarket.clone(), &QueryMsg::GetFeeDenom {})?;
+    assert_eq!(
+        res.fee_denoms,
+        vec![FeeDenom {
+            denom: VALID_NATIVE.to_string(),
+            bps: 50,
+            min_fee: None,
+        }]
+    );
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Update schedule
+    // > 1% JUNO, minimum fee of 50
+    // > 2% USDC
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let new_config = FeeConfig {
+        fee_denoms: vec![
+            FeeDenom {
+                denom: VALID_NATIVE.to_string(),
+                bps: 100,
+                min_fee: Some(Uint128::from(50u32)),
+            },
+            FeeDenom {
+                denom: "uusdc".to_string(),
+                bps: 200,
+                min_fee: None,
+            },
+        ],
+    };
+    let update = ExecuteMsg::UpdateFeeConfig {
+        fee_config: new_config.clone(),
+    };
+
+    // John is not admin
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), fuzionmarket.clone(), &update, &[]);
+    ensure!(res.is_err(), here("John updated fee config", line!(), column!()));
+
+    // Rate over 100%
+    let bad_rate = ExecuteMsg::UpdateFeeConfig {
+        fee_config: FeeConfig {
+            fee_denoms: vec![FeeDenom {
+                denom: VALID_NATIVE.to_string(),
+                bps: 10_001,
+                min_fee: None,
+            }],
+        },
+    };
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &bad_rate,
+        &[],
+    );
+    ensure!(res.is_err(), here("Fee rate over 100%", line!(), column!()));
+
+    // Duplicate denoms
+    let dupes = ExecuteMsg::UpdateFeeConfig {
+        fee_config: FeeConfig {
+            fee_denoms: vec![new_config.fee_denoms[0].clone(), new_config.fee_denoms[0].clone()],
+        },
+    };
+    let res: Result<AppResponse> =
+        router.execute_contract(contract_admin.address.clone(), fuzionmarket.clone(), &dupes, &[]);
+    ensure!(res.is_err(), here("Duplicate fee denoms", line!(), column!()));
+
+    // Admin can update
+    let res: Result<AppResponse> =
+        router.execute_contract(contract_admin.address.clone(), fuzionmarket.clone(), &update, &[]);
+    ensure!(res.is_ok(), here("Admin update fee config", line!(), column!()));
+
+    let res: crate::query::FeeDenomResponse =
+        router.wrap().query_wasm_smart(fuzionmarket.clone(), &QueryMsg::GetFeeDenom {})?;
+    assert_eq!(res.fee_denoms, new_config.fee_denoms);
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Listing
+    // FOR_SALE: 5_000_000 JUNO
+    // PRICE: 20 JVTWO + 1_000 JUNO
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let clm = ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            asks: vec![GenericBalance {
+                native: coins(1_000, VALID_NATIVE),
+                cw20: vec![Cw20CoinVerified {
+                    address: jvtwo.addr(),
+                    amount: Uint128::from(20u32),
+                }],
+                nfts: vec![],
+            }],
+            buyer_restriction: None,
+        },
+    };
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &clm,
+        &coins(5_000_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+
+    let finalize = ExecuteMsg::Finalize {
+        listing_id: 1,
+        expiration: expires_in(router, 10000),
+        start: None,
+    };
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), fuzionmarket.clone(), &finalize, &[]);
+    ensure!(res.is_ok(), here("John finalize", line!(), column!()));
+
+    // Sam's bucket: 1_000 JUNO + 20 JVTWO
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateBucket {},
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("Sam create bucket", line!(), column!()));
+    let sam_c_msg = cw20_base::msg::ExecuteMsg::Send {
+        contract: fuzionmarket.to_string(),
+        amount: Uint128::from(20u32),
+        msg: to_binary(&ReceiveMsg::AddToBucketCw20 {
+            bucket_id: 1,
+        })?,
+    };
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), jvtwo.addr(), &sam_c_msg, &[]);
+    ensure!(res.is_ok(), here("Sam add to bucket", line!(), column!()));
+
+    let buy_msg = ExecuteMsg::BuyListing {
+        listing_id: 1,
+        ask_index: 0,
+        bucket_id: 1,
+        settle: false,
+    };
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), fuzionmarket.clone(), &buy_msg, &[]);
+    ensure!(res.is_ok(), here("Sam buy listing", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Withdraw both sides
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::WithdrawPurchased {
+            listing_id: 1,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Sam withdraw purchased", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket,
+        &ExecuteMsg::RemoveBucket {
+            bucket_id: 1,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John remove bucket", line!(), column!()));
+
+    // Sam: 100_000_000 - 1_000 + (5_000_000 - 1% fee of 50_000)
+    let sam_juno_bal: Coin = router.wrap().query_balance(sam.address.to_string(), VALID_NATIVE)?;
+    assert_eq!(sam_juno_bal.amount, Uint128::from(104_949_000_u32));
+
+    // John: 100_000_000 - 5_000_000 + (1_000 - min fee of 50 <1% would be 10>)
+    let john_juno_bal: Coin =
+        router.wrap().query_balance(john.address.to_string(), VALID_NATIVE)?;
+    assert_eq!(john_juno_bal.amount, Uint128::from(95_000_950_u32));
+    assert_eq!(jvtwo.balance(&router.wrap(), john.address.clone()), Ok(Uint128::from(120u32)));
+
+    // Community Pool: 50_000 + 50
+    let cp_juno_bal: Coin = router.wrap().query_balance(COMMUNITY_POOL, VALID_NATIVE)?;
+    assert_eq!(cp_juno_bal.amount, Uint128::from(50_050_u32));
+
+    Ok(())
+}
+
+// Config & Admin
+// <X> Defaults are set on instantiate
+// <X> Admin handover needs ProposeAdmin & AcceptAdmin from the proposed address
+// <X> Only Admin can update config, old admin loses rights after handover
+// <X> Limits of 0 are rejected
+// <X> max_assets applies to asks, ChangeAsk & Buckets
+// <X> Fees go to the configured address
+#[test]
+fn config_and_admin() -> Result<(), anyhow::Error> {
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    router.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(
+                storage,
+                &sam.address,
+                vec![
+                    Coin::new(100, "denom0"),
+                    Coin::new(100, "denom1"),
+                    Coin::new(100, VALID_NATIVE),
+                ],
+            )
+            .unwrap()
+    });
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Defaults
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: crate::query::ConfigResponse =
+        router.wrap().query_wasm_smart(fuzionmarket.clone(), &QueryMsg::GetConfig {})?;
+    assert_eq!(
+        res.config,
+        Config {
+            admin: contract_admin.address.clone(),
+            pending_admin: None,
+            fee_recipient: FeeRecipient::CommunityPool,
+            limits: Limits::default(),
+            pause: Pause::default(),
+            token_lists: TokenLists::default(),
+        }
+    );
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Admin handover: admin -> max
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let propose = ExecuteMsg::ProposeAdmin {
+        new_admin: max.address.to_string(),
+    };
+
+    // Nothing to accept yet
+    let res: Result<AppResponse> = router.execute_contract(
+        max.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::AcceptAdmin {},
+        &[],
+    );
+    ensure!(res.is_err(), here("Accept without proposal", line!(), column!()));
+
+    // John is not admin
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), fuzionmarket.clone(), &propose, &[]);
+    ensure!(res.is_err(), here("John proposed admin", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &propose,
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin propose max", line!(), column!()));
+
+    // John is not the proposed admin
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::AcceptAdmin {},
+        &[],
+    );
+    ensure!(res.is_err(), here("John accepted admin", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        max.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::AcceptAdmin {},
+        &[],
+    );
+    ensure!(res.is_ok(), here("Max accept admin", line!(), column!()));
+
+    let res: crate::query::ConfigResponse =
+        router.wrap().query_wasm_smart(fuzionmarket.clone(), &QueryMsg::GetConfig {})?;
+    assert_eq!(res.config.admin, max.address);
+    assert_eq!(res.config.pending_admin, None);
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Update config
+    // > Fees paid to "treasury"
+    // > Max of 2 assets per side
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let update = ExecuteMsg::UpdateConfig {
+        fee_recipient: Some(FeeRecipient::Address(Addr::unchecked("treasury"))),
+        limits: Some(Limits {
+            max_assets: 2,
+            ..Limits::default()
+        }),
+    };
+
+    // Old admin lost rights
+    let res: Result<AppResponse> =
+        router.execute_contract(contract_admin.address.clone(), fuzionmarket.clone(), &update, &[]);
+    ensure!(res.is_err(), here("Old admin updated config", line!(), column!()));
+
+    // Limit of 0
+    let res: Result<AppResponse> = router.execute_contract(
+        max.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::UpdateConfig {
+            fee_recipient: None,
+            limits: Some(Limits {
+                max_assets: 0,
+                ..Limits::default()
+            }),
+        },
+        &[],
+    );
+    ensure!(res.is_err(), here("Max assets of 0", line!(), column!()));
+
+    let res: Result<AppResponse> =
+        router.execute_contract(max.address.clone(), fuzionmarket.clone(), &update, &[]);
+    ensure!(res.is_ok(), here("Max update config", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Asset limits
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let ask_of = |n: u128| CreateListingMsg {
+        asks: vec![GenericBalance {
+            native: (0..n).map(|i| Coin::new(1, format!("denom{i}"))).collect(),
+            cw20: vec![],
+            nfts: vec![],
+        }],
+        buyer_restriction: None,
+    };
+
+    // Ask with 3 assets
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateListing {
+            create_msg: ask_of(3),
+        },
+        &coins(1_000_000, VALID_NATIVE),
+    );
+    ensure!(res.is_err(), here("Ask over asset limit", line!(), column!()));
+
+    // Ask with 2 assets
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateListing {
+            create_msg: ask_of(2),
+        },
+        &coins(1_000_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("Ask at asset limit", line!(), column!()));
+
+    // Changing ask to 3 assets
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::ChangeAsk {
+            listing_id: 1,
+            new_asks: ask_of(3).asks,
+        },
+        &[],
+    );
+    ensure!(res.is_err(), here("Change ask over asset limit", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Fees go to the configured address
+    // Listing: 1_000_000 JUNO for 1 denom0 + 1 denom1
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::Finalize {
+            listing_id: 1,
+            expiration: expires_in(router, 10000),
+            start: None,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John finalize", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateBucket {},
+        &[Coin::new(1, "denom0"), Coin::new(1, "denom1")],
+    );
+    ensure!(res.is_ok(), here("Sam create bucket", line!(), column!()));
+
+    // Bucket would hold 3 assets
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::AddToBucket {
+            bucket_id: 1,
+        },
+        &coins(1, VALID_NATIVE),
+    );
+    ensure!(res.is_err(), here("Bucket over asset limit", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::BuyListing {
+            listing_id: 1,
+            ask_index: 0,
+            bucket_id: 1,
+            settle: false,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Sam buy listing", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket,
+        &ExecuteMsg::WithdrawPurchased {
+            listing_id: 1,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Sam withdraw purchased", line!(), column!()));
+
+    // 0.5% of 1_000_000
+    let treasury_bal: Coin = router.wrap().query_balance("treasury", VALID_NATIVE)?;
+    assert_eq!(treasury_bal.amount, Uint128::from(5_000_u32));
+    let cp_bal: Coin = router.wrap().query_balance(COMMUNITY_POOL, VALID_NATIVE)?;
+    assert_eq!(cp_bal.amount, Uint128::zero());
+
+    Ok(())
+}
+
+// Migration
+// <X> Different contract, downgrades & unknown versions are rejected
+// <X> v0.2.0 Listings, Buckets & fee config are carried over
+// <X> Migrating again is a no-op
+// <X> Migrated Listings & Buckets can still be bought & withdrawn
+#[test]
+fn migrate_from_v0_2_0() -> Result<(), anyhow::Error> {
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    let router = give_natives(&contract_admin, &mut router);
+
+    let legacy_id = router.store_code(fuzionmarket_v0_2_0_contract());
+    let new_id = router.store_code(fuzionmarket_contract());
+
+    let legacy = |contract: &str, version: &str| LegacyInstantiateMsg {
+        contract: contract.to_string(),
+        version: version.to_string(),
+    };
+
+    // v0.2.0 market holding every Listing & Bucket's funds
+    let fuzionmarket = router.instantiate_contract(
+        legacy_id,
+        contract_admin.address.clone(),
+        &legacy("crates.io:fuzion_market", "0.2.0"),
+        &coins(1_002_800, VALID_NATIVE),
+        "jv",
+        Some(contract_admin.address.to_string()),
+    )?;
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Invalid migrations
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+    // v0.2.0 has no admin, so one must be given
+    let res: Result<AppResponse> = router.migrate_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &MigrateMsg::default(),
+        new_id,
+    );
+    ensure!(res.is_err(), here("Migrate without admin", line!(), column!()));
+
+    // Different contract
+    let other = router.instantiate_contract(
+        legacy_id,
+        contract_admin.address.clone(),
+        &legacy("crates.io:other", "0.2.0"),
+        &[],
+        "other",
+        Some(contract_admin.address.to_string()),
+    )?;
+    let res: Result<AppResponse> = router.migrate_contract(
+        contract_admin.address.clone(),
+        other,
+        &MigrateMsg {
+            admin: Some(contract_admin.address.to_string()),
+            ..MigrateMsg::default()
+        },
+        new_id,
+    );
+    ensure!(res.is_err(), here("Migrate other contract", line!(), column!()));
+
+    // Downgrade
+    let newer = router.instantiate_contract(
+        legacy_id,
+        contract_admin.address.clone(),
+        &legacy("crates.io:fuzion_market", "99.0.0"),
+        &[],
+        "newer",
+        Some(contract_admin.address.to_string()),
+    )?;
+    let res: Result<AppResponse> = router.migrate_contract(
+        contract_admin.address.clone(),
+        newer,
+        &MigrateMsg::default(),
+        new_id,
+    );
+    ensure!(res.is_err(), here("Downgrade", line!(), column!()));
+
+    // Older than any known layout
+    let older = router.instantiate_contract(
+        legacy_id,
+        contract_admin.address.clone(),
+        &legacy("crates.io:fuzion_market", "0.1.0"),
+        &[],
+        "older",
+        Some(contract_admin.address.to_string()),
+    )?;
+    let res: Result<AppResponse> = router.migrate_contract(
+        contract_admin.address.clone(),
+        older,
+        &MigrateMsg {
+            admin: Some(contract_admin.address.to_string()),
+            ..MigrateMsg::default()
+        },
+        new_id,
+    );
+    ensure!(res.is_err(), here("Migrate unsupported version", line!(), column!()));
+
+    // Between known layouts
+    let between = router.instantiate_contract(
+        legacy_id,
+        contract_admin.address.clone(),
+        &legacy("crates.io:fuzion_market", "0.2.5"),
+        &[],
+        "between",
+        Some(contract_admin.address.to_string()),
+    )?;
+    let res: Result<AppResponse> = router.migrate_contract(
+        contract_admin.address.clone(),
+        between,
+        &MigrateMsg {
+            admin: Some(contract_admin.address.to_string()),
+            ..MigrateMsg::default()
+        },
+        new_id,
+    );
+    ensure!(res.is_err(), here("Migrate from between layouts", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Migrate v0.2.0 -> current
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.migrate_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &MigrateMsg {
+            admin: Some(contract_admin.address.to_string()),
+            ..MigrateMsg::default()
+        },
+        new_id,
+    );
+    ensure!(res.is_ok(), here("Migrate from v0.2.0", line!(), column!()));
+
+    let version = cw2::query_contract_info(&router.wrap(), fuzionmarket.to_string())?;
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+
+    let res: crate::query::ConfigResponse =
+        router.wrap().query_wasm_smart(fuzionmarket.clone(), &QueryMsg::GetConfig {})?;
+    assert_eq!(res.config.admin, contract_admin.address);
+    assert_eq!(res.config.fee_recipient, FeeRecipient::CommunityPool);
+
+    // Old JUNO fee carried over
+    let res: crate::query::FeeDenomResponse =
+        router.wrap().query_wasm_smart(fuzionmarket.clone(), &QueryMsg::GetFeeDenom {})?;
+    assert_eq!(
+        res.fee_denoms,
+        vec![FeeDenom {
+            denom: VALID_NATIVE.to_string(),
+            bps: 50,
+            min_fee: None,
+        }]
+    );
+
+    let res: crate::query::MultiListingResponse = router.wrap().query_wasm_smart(
+        fuzionmarket.clone(),
+        &QueryMsg::GetListingsByOwner {
+            owner: sam.address.to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?;
+    assert_eq!(res.listings.len(), 1);
+    assert_eq!(res.listings[0].fee_amount, coins(5_000, VALID_NATIVE));
+
+    let res: crate::query::GetBucketsResponse = router.wrap().query_wasm_smart(
+        fuzionmarket.clone(),
+        &QueryMsg::GetBuckets {
+            bucket_owner: john.address.to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?;
+    assert_eq!(res.buckets.len(), 1);
+    assert_eq!(res.buckets[0].0, 1);
+
+    let res: crate::query::StatsResponse =
+        router.wrap().query_wasm_smart(fuzionmarket.clone(), &QueryMsg::GetStats {})?;
+    assert_eq!(
+        res.stats.listings,
+        ListingCounts {
+            being_prepared: 1,
+            finalized_ready: 0,
+            closed: 1,
+        }
+    );
+    assert_eq!(res.stats.trades, 0);
+
+    // Migrating again is a no-op
+    let res: Result<AppResponse> = router.migrate_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &MigrateMsg::default(),
+        new_id,
+    );
+    ensure!(res.is_ok(), here("Migrate current version", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Migrated Listings & Buckets still work
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::WithdrawPurchased {
+            listing_id: 1,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Sam withdraw purchased", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        max.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::DeleteListing {
+            listing_id: 2,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Max delete listing", line!(), column!()));
+
+    // John's proceeds still owe their fee, so they can't be withdrawn in part
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::WithdrawFromBucket {
+            bucket_id: 1,
+            assets: GenericBalance {
+                native: coins(1_000, VALID_NATIVE),
+                cw20: vec![],
+                nfts: vec![],
+            },
+        },
+        &[],
+    );
+    ensure!(res.is_err(), here("John partial withdraw proceeds", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::RemoveBucket {
+            bucket_id: 1,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John remove bucket", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::RemoveBucket {
+            bucket_id: 2,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Sam remove bucket", line!(), column!()));
+
+    let bal = |addr: &str| router.wrap().query_balance(addr, VALID_NATIVE).map(|c| c.amount);
+    assert_eq!(bal(sam.address.as_str())?, Uint128::from(995_300_u32));
+    assert_eq!(bal(max.address.as_str())?, Uint128::from(500_u32));
+    assert_eq!(bal(john.address.as_str())?, Uint128::from(1_990_u32));
+    assert_eq!(bal(COMMUNITY_POOL)?, Uint128::from(5_010_u32));
+    assert_eq!(bal(fuzionmarket.as_str())?, Uint128::zero());
+
+    Ok(())
+}
+
+// Pause
+// <X> Only Admin can pause
+// <X> Each flag blocks its messages, including receive hooks
+// <X> Admin messages work while paused
+// <X> Withdraw-only mode still lets assets out
+#[test]
+fn pause() -> Result<(), anyhow::Error> {
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let clm = ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            asks: vec![GenericBalance {
+                native: coins(1_000, VALID_NATIVE),
+                cw20: vec![],
+                nfts: vec![],
+            }],
+            buyer_restriction: None,
+        },
+    };
+    let set_pause = |create: bool, buy: bool, withdraw: bool| ExecuteMsg::SetPause {
+        pause: Pause {
+            create,
+            buy,
+            withdraw,
+        },
+    };
+
+    // Listing 1 & Bucket 1 created while unpaused
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &clm,
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::Finalize {
+            listing_id: 1,
+            expiration: expires_in(router, 10000),
+            start: None,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John finalize", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateBucket {},
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("Sam create bucket", line!(), column!()));
+
+    // Listing 2 stays BeingPrepared
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &clm,
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John create listing 2", line!(), column!()));
+    let remove_from_listing = ExecuteMsg::RemoveFromListing {
+        listing_id: 2,
+        assets: GenericBalance {
+            native: coins(100, VALID_NATIVE),
+            cw20: vec![],
+            nfts: vec![],
+        },
+    };
+
+    // John is not admin
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &set_pause(true, true, true),
+        &[],
+    );
+    ensure!(res.is_err(), here("John set pause", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Everything paused
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &set_pause(true, true, true),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin pause all", line!(), column!()));
+
+    let res: crate::query::ConfigResponse =
+        router.wrap().query_wasm_smart(fuzionmarket.clone(), &QueryMsg::GetConfig {})?;
+    assert_eq!(
+        res.config.pause,
+        Pause {
+            create: true,
+            buy: true,
+            withdraw: true,
+        }
+    );
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::RemoveBucket {
+            bucket_id: 1,
+        },
+        &[],
+    );
+    ensure!(res.is_err(), here("Sam remove bucket while paused", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &remove_from_listing,
+        &[],
+    );
+    ensure!(res.is_err(), here("John remove from listing while paused", line!(), column!()));
+
+    // Admin messages still work
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::UpdateConfig {
+            fee_recipient: None,
+            limits: None,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin update config while paused", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Withdraw-only
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &set_pause(true, false, false),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin pause create", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &clm,
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_err(), here("John create listing while paused", line!(), column!()));
+
+    // cw20 entry point is paused too
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        jvone.addr(),
+        &cw20_base::msg::ExecuteMsg::Send {
+            contract: fuzionmarket.to_string(),
+            amount: Uint128::from(10u32),
+            msg: to_binary(&ReceiveMsg::CreateBucketCw20 {})?,
+        },
+        &[],
+    );
+    ensure!(res.is_err(), here("John create cw20 bucket while paused", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &set_pause(true, true, false),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin pause create & buy", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::BuyListing {
+            listing_id: 1,
+            ask_index: 0,
+            bucket_id: 1,
+            settle: false,
+        },
+        &[],
+    );
+    ensure!(res.is_err(), here("Sam buy while paused", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::RemoveBucket {
+            bucket_id: 1,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Sam remove bucket in withdraw-only", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &remove_from_listing,
+        &[],
+    );
+    ensure!(res.is_ok(), here("John remove from listing in withdraw-only", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Unpaused
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &set_pause(false, false, false),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin unpause", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket,
+        &clm,
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John create listing after unpause", line!(), column!()));
+
+    Ok(())
+}
+
+// Single Lookups
+// <X> GetListing by status, purchasable flag & not found
+// <X> GetBucket only finds the owner's Bucket
+#[test]
+fn single_lookups() -> Result<(), anyhow::Error> {
+    use crate::query::{BucketResponse, ListingResponse};
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateListing {
+            create_msg: CreateListingMsg {
+                asks: vec![GenericBalance {
+                    native: coins(1_000, VALID_NATIVE),
+                    cw20: vec![],
+                    nfts: vec![],
+                }],
+                buyer_restriction: None,
+            },
+        },
+        &coins(5_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateBucket {},
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("Sam create bucket", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // GetListing
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let get_listing = QueryMsg::GetListing {
+        listing_id: 1,
+    };
+
+    // Being prepared
+    let res: ListingResponse =
+        router.wrap().query_wasm_smart(fuzionmarket.clone(), &get_listing)?;
+    assert_eq!(res.listing.creator, john.address);
+    assert_eq!(res.listing.for_sale.native, coins(5_000, VALID_NATIVE));
+    assert!(!res.purchasable);
+
+    // Finalized
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::Finalize {
+            listing_id: 1,
+            expiration: expires_in(router, 600),
+            start: None,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John finalize", line!(), column!()));
+    let res: ListingResponse =
+        router.wrap().query_wasm_smart(fuzionmarket.clone(), &get_listing)?;
+    assert!(res.purchasable);
+
+    // Expired
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 20;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(601);
+    });
+    let res: ListingResponse =
+        router.wrap().query_wasm_smart(fuzionmarket.clone(), &get_listing)?;
+    assert!(!res.purchasable);
+
+    // Not found
+    let res: Result<ListingResponse, _> = router.wrap().query_wasm_smart(
+        fuzionmarket.clone(),
+        &QueryMsg::GetListing {
+            listing_id: 2,
+        },
+    );
+    ensure!(res.is_err(), here("Listing 2 found", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // GetBucket
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: BucketResponse = router.wrap().query_wasm_smart(
+        fuzionmarket.clone(),
+        &QueryMsg::GetBucket {
+            owner: sam.address.to_string(),
+            bucket_id: 1,
+        },
+    )?;
+    assert_eq!(res.bucket.owner, sam.address);
+    assert_eq!(res.bucket.funds.native, coins(1_000, VALID_NATIVE));
+
+    // Bucket 1 isn't John's
+    let res: Result<BucketResponse, _> = router.wrap().query_wasm_smart(
+        fuzionmarket,
+        &QueryMsg::GetBucket {
+            owner: john.address.to_string(),
+            bucket_id: 1,
+        },
+    );
+    ensure!(res.is_err(), here("John's Bucket 1 found", line!(), column!()));
+
+    Ok(())
+}
+
+// Pagination
+// <X> GetAllListings, GetListingsByOwner & GetBuckets page with start_after & limit
+// <X> Limit is capped, a limit of 0 still returns a page
+#[test]
+fn pagination() -> Result<(), anyhow::Error> {
+    use crate::query::{GetBucketsResponse, MultiListingResponse};
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    let router = give_natives(&max, router);
+
+    let clm = ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            asks: vec![GenericBalance {
+                native: coins(1_000, VALID_NATIVE),
+                cw20: vec![],
+                nfts: vec![],
+            }],
+            buyer_restriction: None,
+        },
+    };
+
+    // Listings 1, 2, 4, 5 by John | 3 by Max
+    for user in [&john, &john, &max, &john, &john] {
+        let res: Result<AppResponse> = router.execute_contract(
+            user.address.clone(),
+            fuzionmarket.clone(),
+            &clm,
+            &coins(1_000, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(format!("{} create listing", user.name), line!(), column!()));
+    }
+
+    // Buckets 1, 2, 3 by Sam
+    for _ in 0..3 {
+        let res: Result<AppResponse> = router.execute_contract(
+            sam.address.clone(),
+            fuzionmarket.clone(),
+            &ExecuteMsg::CreateBucket {},
+            &coins(1_000, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here("Sam create bucket", line!(), column!()));
+    }
+
+    let ids = |res: &MultiListingResponse| res.listings.iter().map(|l| l.id).collect::<Vec<_>>();
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // GetAllListings
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let mut pages = vec![];
+    let mut start_after = None;
+    loop {
+        let res: MultiListingResponse = router.wrap().query_wasm_smart(
+            fuzionmarket.clone(),
+            &QueryMsg::GetAllListings {
+                start_after,
+                limit: Some(2),
+            },
+        )?;
+        pages.push(ids(&res));
+        start_after = res.next;
+        if start_after.is_none() {
+            break;
+        }
+    }
+    assert_eq!(pages, vec![vec![1, 2], vec![3, 4], vec![5]]);
+
+    // Limit is capped
+    let res: MultiListingResponse = router.wrap().query_wasm_smart(
+        fuzionmarket.clone(),
+        &QueryMsg::GetAllListings {
+            start_after: None,
+            limit: Some(1_000),
+        },
+    )?;
+    assert_eq!(ids(&res), vec![1, 2, 3, 4, 5]);
+    assert_eq!(res.next, None);
+
+    // Limit of 0 still returns a page
+    let res: MultiListingResponse = router.wrap().query_wasm_smart(
+        fuzionmarket.clone(),
+        &QueryMsg::GetAllListings {
+            start_after: Some(4),
+            limit: Some(0),
+        },
+    )?;
+    assert_eq!(ids(&res), vec![5]);
+    assert_eq!(res.next, None);
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // GetListingsByOwner
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: MultiListingResponse = router.wrap().query_wasm_smart(
+        fuzionmarket.clone(),
+        &QueryMsg::GetListingsByOwner {
+            owner: john.address.to_string(),
+            start_after: None,
+            limit: Some(2),
+        },
+    )?;
+    assert_eq!(ids(&res), vec![1, 2]);
+    assert_eq!(res.next, Some(2));
+
+    let res: MultiListingResponse = router.wrap().query_wasm_smart(
+        fuzionmarket.clone(),
+        &QueryMsg::GetListingsByOwner {
+            owner: john.address.to_string(),
+            start_after: res.next,
+            limit: Some(2),
+        },
+    )?;
+    assert_eq!(ids(&res), vec![4, 5]);
+    assert_eq!(res.next, None);
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // GetBuckets
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: GetBucketsResponse = router.wrap().query_wasm_smart(
+        fuzionmarket.clone(),
+        &QueryMsg::GetBuckets {
+            bucket_owner: sam.address.to_string(),
+            start_after: None,
+            limit: Some(2),
+        },
+    )?;
+    assert_eq!(res.buckets.iter().map(|b| b.0).collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(res.next, Some(2));
+
+    let res: GetBucketsResponse = router.wrap().query_wasm_smart(
+        fuzionmarket,
+        &QueryMsg::GetBuckets {
+            bucket_owner: sam.address.to_string(),
+            start_after: res.next,
+            limit: Some(2),
+        },
+    )?;
+    assert_eq!(res.buckets.iter().map(|b| b.0).collect::<Vec<_>>(), vec![3]);
+    assert_eq!(res.next, None);
+
+    Ok(())
+}
+
+// Market Browsing
+// <X> Newest & SoonestExpiring sorts
+// <X> Status, buyer & asset filters
+// <X> Paging carries on after the last Listing on a page is removed
+// <X> Expired Listings drop out
+// <X> Scan cap returns a short page pointing past what was read
+#[test]
+fn market_browse() -> Result<(), anyhow::Error> {
+    use crate::query::{
+        AssetFilter, MarketCursor, MarketFilter, MarketListingsResponse, MarketSort,
+    };
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, jvtwo, _jvtre, _neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&max, router);
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Listings, all selling 1_000 JUNO
+    // 1 | ask 10 JVTWO | expires in 1000
+    // 2 | ask 1_000 JUNO | expires in 600 | whitelisted to Sam
+    // 3 | ask 1_000 JUNO | not finalized
+    // 4 | ask 1_000 JUNO | expires in 2000 | bought by Max
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let juno_ask = GenericBalance {
+        native: coins(1_000, VALID_NATIVE),
+        cw20: vec![],
+        nfts: vec![],
+    };
+    let listings = [
+        (
+            GenericBalance {
+                native: vec![],
+                cw20: vec![Cw20CoinVerified {
+                    address: jvtwo.addr(),
+                    amount: Uint128::from(10u32),
+                }],
+                nfts: vec![],
+            },
+            None,
+            Some(1000),
+        ),
+        (juno_ask.clone(), Some(BuyerRestriction::Address(sam.address.clone())), Some(600)),
+        (juno_ask.clone(), None, None),
+        (juno_ask.clone(), None, Some(2000)),
+    ];
+    for (id, (ask, buyer_restriction, seconds)) in listings.into_iter().enumerate() {
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &ExecuteMsg::CreateListing {
+                create_msg: CreateListingMsg {
+                    asks: vec![ask],
+                    buyer_restriction,
+                },
+            },
+            &coins(1_000, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+
+        if let Some(seconds) = seconds {
+            let res: Result<AppResponse> = router.execute_contract(
+                john.address.clone(),
+                fuzionmarket.clone(),
+                &ExecuteMsg::Finalize {
+                    listing_id: id as u64 + 1,
+                    expiration: expires_in(router, seconds),
+                    start: None,
+                },
+                &[],
+            );
+            ensure!(res.is_ok(), here("John finalize", line!(), column!()));
+        }
+    }
+
+    let res: Result<AppResponse> = router.execute_contract(
+        max.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateBucket {},
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("Max create bucket", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        max.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::BuyListing {
+            listing_id: 4,
+            ask_index: 0,
+            bucket_id: 1,
+            settle: false,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Max buy listing 4", line!(), column!()));
+
+    let browse = |router: &App,
+                  filter: Option<MarketFilter>,
+                  sort: MarketSort,
+                  start_after: Option<MarketCursor>,
+                  limit: Option<u32>|
+     -> Result<(Vec<u64>, Option<MarketCursor>)> {
+        let res: MarketListingsResponse = router.wrap().query_wasm_smart(
+            fuzionmarket.clone(),
+            &QueryMsg::GetListingsForMarket {
+                filter,
+                sort: Some(sort),
+                start_after,
+                limit,
+            },
+        )?;
+        Ok((res.listings.iter().map(|l| l.id).collect(), res.next))
+    };
+    let any = MarketFilter {
+        status: None,
+        include_expired: true,
+        upcoming: false,
+        whitelisted_for: None,
+        for_sale_contains: None,
+        ask_contains: None,
+    };
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Sorting
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+    // Default only shows purchasable Listings
+    assert_eq!(browse(router, None, MarketSort::Newest, None, None)?, (vec![2, 1], None));
+    assert_eq!(browse(router, None, MarketSort::SoonestExpiring, None, None)?, (vec![2, 1], None));
+
+    assert_eq!(
+        browse(router, Some(any.clone()), MarketSort::Newest, None, None)?,
+        (vec![4, 3, 2, 1], None)
+    );
+    assert_eq!(
+        browse(router, Some(any.clone()), MarketSort::SoonestExpiring, None, None)?,
+        (vec![2, 1, 4, 3], None)
+    );
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Filters
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let filter = |f: fn(&mut MarketFilter)| {
+        let mut filter = MarketFilter::default();
+        f(&mut filter);
+        Some(filter)
+    };
+
+    assert_eq!(
+        browse(
+            router,
+            filter(|f| f.status = Some(Status::Closed)),
+            MarketSort::Newest,
+            None,
+            None
+        )?,
+        (vec![4], None)
+    );
+    assert_eq!(
+        browse(
+            router,
+            filter(|f| f.whitelisted_for = Some("max".to_string())),
+            MarketSort::Newest,
+            None,
+            None
+        )?,
+        (vec![1], None)
+    );
+    assert_eq!(
+        browse(
+            router,
+            filter(|f| f.whitelisted_for = Some("sam".to_string())),
+            MarketSort::Newest,
+            None,
+            None
+        )?,
+        (vec![2, 1], None)
+    );
+    assert_eq!(
+        browse(
+            router,
+            Some(MarketFilter {
+                ask_contains: Some(AssetFilter::Cw20 {
+                    address: jvtwo.addr().to_string(),
+                }),
+                ..MarketFilter::default()
+            }),
+            MarketSort::Newest,
+            None,
+            None
+        )?,
+        (vec![1], None)
+    );
+    assert_eq!(
+        browse(
+            router,
+            filter(|f| f.for_sale_contains = Some(AssetFilter::Native {
+                denom: "ujunox".to_string(),
+            })),
+            MarketSort::Newest,
+            None,
+            None
+        )?,
+        (vec![2, 1], None)
+    );
+    assert_eq!(
+        browse(
+            router,
+            filter(|f| f.for_sale_contains = Some(AssetFilter::Native {
+                denom: "uatom".to_string(),
+            })),
+            MarketSort::Newest,
+            None,
+            None
+        )?,
+        (vec![], None)
+    );
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Paging
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let (ids, next) = browse(router, None, MarketSort::Newest, None, Some(1))?;
+    assert_eq!((ids, next.as_ref().map(|c| c.id)), (vec![2], Some(2)));
+    assert_eq!(browse(router, None, MarketSort::Newest, next, Some(1))?, (vec![1], None));
+
+    let (ids, next) =
+        browse(router, Some(any.clone()), MarketSort::SoonestExpiring, None, Some(3))?;
+    assert_eq!((ids, next.as_ref().map(|c| c.id)), (vec![2, 1, 4], Some(4)));
+
+    // The last Listing on the page is gone, the next page still picks up after it
+    let res: Result<AppResponse> = router.execute_contract(
+        max.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::WithdrawPurchased {
+            listing_id: 4,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Max withdraw listing 4", line!(), column!()));
+    assert_eq!(
+        browse(router, Some(any), MarketSort::SoonestExpiring, next, Some(3))?,
+        (vec![3], None)
+    );
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Expiry
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 200;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(700);
+    });
+    assert_eq!(browse(router, None, MarketSort::Newest, None, None)?, (vec![1], None));
+    assert_eq!(browse(router, None, MarketSort::SoonestExpiring, None, None)?, (vec![1], None));
+
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 200;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(700);
+    });
+    assert_eq!(browse(router, None, MarketSort::Newest, None, None)?, (vec![], None));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Scan cap
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    for _ in 0..crate::query::MAX_MARKET_SCAN {
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &ExecuteMsg::CreateListing {
+                create_msg: CreateListingMsg {
+                    asks: vec![juno_ask.clone()],
+                    buyer_restriction: None,
+                },
+            },
+            &coins(1, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here("John create unfinalized listing", line!(), column!()));
+    }
+
+    // Only unfinalized Listings are read, so the page is empty but points past them
+    let (ids, next) = browse(router, None, MarketSort::Newest, None, None)?;
+    assert_eq!((ids, next.as_ref().map(|c| c.id)), (vec![], Some(5)));
+    assert_eq!(browse(router, None, MarketSort::Newest, next, None)?, (vec![], None));
+
+    Ok(())
+}
+
+// Whitelisted Listings
+// <X> Only purchasable Listings naming the buyer are returned
+// <X> Buyer must be a valid address
+// <X> Expired Listings drop out
+#[test]
+fn whitelisted_listings() -> Result<(), anyhow::Error> {
+    use crate::query::MultiListingResponse;
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Listings, all asking 1_000 JUNO
+    // 1 | Sam | expires in 2000
+    // 2 | Sam | not finalized
+    // 3 | public | expires in 2000
+    // 4 | Max | expires in 2000
+    // 5 | Sam | expires in 600
+    // 6 | Sam | expires in 2000 | bought by Sam
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let listings = [
+        (Some(&sam), Some(2000)),
+        (Some(&sam), None),
+        (None, Some(2000)),
+        (Some(&max), Some(2000)),
+        (Some(&sam), Some(600)),
+        (Some(&sam), Some(2000)),
+    ];
+    for (id, (buyer, seconds)) in listings.into_iter().enumerate() {
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &ExecuteMsg::CreateListing {
+                create_msg: CreateListingMsg {
+                    asks: vec![GenericBalance {
+                        native: coins(1_000, VALID_NATIVE),
+                        cw20: vec![],
+                        nfts: vec![],
+                    }],
+                    buyer_restriction: buyer.map(|u| BuyerRestriction::Address(u.address.clone())),
+                },
+            },
+            &coins(1_000, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+
+        if let Some(seconds) = seconds {
+            let res: Result<AppResponse> = router.execute_contract(
+                john.address.clone(),
+                fuzionmarket.clone(),
+                &ExecuteMsg::Finalize {
+                    listing_id: id as u64 + 1,
+                    expiration: expires_in(router, seconds),
+                    start: None,
+                },
+                &[],
+            );
+            ensure!(res.is_ok(), here("John finalize", line!(), column!()));
+        }
+    }
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateBucket {},
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("Sam create bucket", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::BuyListing {
+            listing_id: 6,
+            ask_index: 0,
+            bucket_id: 1,
+            settle: false,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Sam buy listing 6", line!(), column!()));
+
+    let whitelisted = |router: &App, buyer: &str, start_after: Option<u64>, limit: Option<u32>| {
+        router.wrap().query_wasm_smart::<MultiListingResponse>(
+            fuzionmarket.clone(),
+            &QueryMsg::GetWhitelistedListings {
+                buyer: buyer.to_string(),
+                start_after,
+                limit,
+            },
+        )
+    };
+    let ids = |res: &MultiListingResponse| res.listings.iter().map(|l| l.id).collect::<Vec<_>>();
+
+    let res = whitelisted(router, "sam", None, None)?;
+    assert_eq!((ids(&res), res.next), (vec![1, 5], None));
+
+    let res = whitelisted(router, "sam", None, Some(1))?;
+    assert_eq!((ids(&res), res.next), (vec![1], Some(1)));
+    let res = whitelisted(router, "sam", res.next, Some(1))?;
+    assert_eq!((ids(&res), res.next), (vec![5], None));
+
+    let res = whitelisted(router, "max", None, None)?;
+    assert_eq!(ids(&res), vec![4]);
+
+    // Buyer must be a valid address
+    let res = whitelisted(router, "", None, None);
+    ensure!(res.is_err(), here("Empty buyer", line!(), column!()));
+    let res = whitelisted(router, "1", None, None);
+    ensure!(res.is_err(), here("Sentinel buyer", line!(), column!()));
+
+    // Listing 5 expires
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 200;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(601);
+    });
+    let res = whitelisted(router, "sam", None, None)?;
+    assert_eq!(ids(&res), vec![1]);
+
+    Ok(())
+}
+
+// Buy with Funds
+// <X> Native & cw20 payments must match the ask
+// <X> Sold Listings can't be bought again
+// <X> Blocked by the buy & withdraw pauses
+// <X> Both sides are paid out, minus fees, in one tx
+#[test]
+fn buy_with_funds() -> Result<(), anyhow::Error> {
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, jvtwo, _jvtre, _neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Listings
+    // 1 | 1_000_000 JUNO for 20 JVTWO
+    // 2 | 10 JVONE for 2_000 JUNO
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateListing {
+            create_msg: CreateListingMsg {
+                asks: vec![GenericBalance {
+                    native: vec![],
+                    cw20: vec![Cw20CoinVerified {
+                        address: jvtwo.addr(),
+                        amount: Uint128::from(20u32),
+                    }],
+                    nfts: vec![],
+                }],
+                buyer_restriction: None,
+            },
+        },
+        &coins(1_000_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John create listing 1", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        jvone.addr(),
+        &cw20_base::msg::ExecuteMsg::Send {
+            contract: fuzionmarket.to_string(),
+            amount: Uint128::from(10u32),
+            msg: to_binary(&ReceiveMsg::CreateListingCw20 {
+                create_msg: CreateListingMsg {
+                    asks: vec![GenericBalance {
+                        native: coins(2_000, VALID_NATIVE),
+                        cw20: vec![],
+                        nfts: vec![],
+                    }],
+                    buyer_restriction: None,
+                },
+            })?,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John create listing 2", line!(), column!()));
+
+    for listing_id in [1, 2] {
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &ExecuteMsg::Finalize {
+                listing_id,
+                expiration: expires_in(router, 1000),
+                start: None,
+            },
+            &[],
+        );
+        ensure!(res.is_ok(), here("John finalize", line!(), column!()));
+    }
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Natives
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let buy_2 = ExecuteMsg::BuyListingWithFunds {
+        listing_id: 2,
+        ask_index: 0,
+    };
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &buy_2,
+        &coins(1_999, VALID_NATIVE),
+    );
+    ensure!(res.is_err(), here("Sam underpay listing 2", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &buy_2,
+        &coins(2_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("Sam buy listing 2", line!(), column!()));
+
+    // Already sold
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &buy_2,
+        &coins(2_000, VALID_NATIVE),
+    );
+    ensure!(res.is_err(), here("Sam buy listing 2 again", line!(), column!()));
+
+    assert_eq!(jvone.balance(&router.wrap(), sam.address.clone())?, Uint128::from(110u32));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // CW20
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let buy_1 = |amount: u32| cw20_base::msg::ExecuteMsg::Send {
+        contract: fuzionmarket.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&ReceiveMsg::BuyListingCw20 {
+            listing_id: 1,
+            ask_index: 0,
+        })
+        .unwrap(),
+    };
+
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), jvtwo.addr(), &buy_1(19), &[]);
+    ensure!(res.is_err(), here("Sam underpay listing 1", line!(), column!()));
+
+    // Buying is paused
+    let pause = |buy: bool, withdraw: bool| ExecuteMsg::SetPause {
+        pause: Pause {
+            create: false,
+            buy,
+            withdraw,
+        },
+    };
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &pause(true, false),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin pause buy", line!(), column!()));
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), jvtwo.addr(), &buy_1(20), &[]);
+    ensure!(res.is_err(), here("Sam buy listing 1 while paused", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &pause(false, false),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin unpause buy", line!(), column!()));
+
+    // Both sides are paid out, so withdraw-paused blocks it too
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &pause(false, true),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin pause withdraw", line!(), column!()));
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), jvtwo.addr(), &buy_1(20), &[]);
+    ensure!(res.is_err(), here("Sam buy listing 1 while withdraw paused", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &pause(false, false),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin unpause withdraw", line!(), column!()));
+
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), jvtwo.addr(), &buy_1(20), &[]);
+    ensure!(res.is_ok(), here("Sam buy listing 1", line!(), column!()));
+
+    assert_eq!(jvtwo.balance(&router.wrap(), john.address.clone())?, Uint128::from(120u32));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Balances
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let bal = |addr: &str| router.wrap().query_balance(addr, VALID_NATIVE).map(|c| c.amount);
+
+    // John: 100_000_000 - 1_000_000 + (2_000 - 0.5% fee of 10)
+    assert_eq!(bal(john.address.as_str())?, Uint128::from(99_001_990_u32));
+    // Sam: 100_000_000 - 2_000 + (1_000_000 - 0.5% fee of 5_000)
+    assert_eq!(bal(sam.address.as_str())?, Uint128::from(100_993_000_u32));
+    assert_eq!(bal(COMMUNITY_POOL)?, Uint128::from(5_010_u32));
+    assert_eq!(bal(fuzionmarket.as_str())?, Uint128::zero());
+
+    // Nothing left to withdraw
+    let res: crate::query::MultiListingResponse = router.wrap().query_wasm_smart(
+        fuzionmarket,
+        &QueryMsg::GetAllListings {
+            start_after: None,
+            limit: None,
+        },
+    )?;
+    assert!(res.listings.is_empty());
+
+    Ok(())
+}
+
+// Settled Buy
+// <X> Blocked while withdraws are paused
+// <X> Listing & Bucket are removed once settled
+// <X> Balance checks after settlement
+#[test]
+fn settled_buy() -> Result<(), anyhow::Error> {
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, jvtwo, _jvtre, _neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Listing
+    // FOR_SALE: 1_000_000 JUNO
+    // PRICE: 20 JVTWO + 1_000 JUNO
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateListing {
+            create_msg: CreateListingMsg {
+                asks: vec![GenericBalance {
+                    native: coins(1_000, VALID_NATIVE),
+                    cw20: vec![Cw20CoinVerified {
+                        address: jvtwo.addr(),
+                        amount: Uint128::from(20u32),
+                    }],
+                    nfts: vec![],
+                }],
+                buyer_restriction: None,
+            },
+        },
+        &coins(1_000_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::Finalize {
+            listing_id: 1,
+            expiration: expires_in(router, 1000),
+            start: None,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John finalize", line!(), column!()));
+
+    // Sam's bucket: 1_000 JUNO + 20 JVTWO
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateBucket {},
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("Sam create bucket", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        jvtwo.addr(),
+        &cw20_base::msg::ExecuteMsg::Send {
+            contract: fuzionmarket.to_string(),
+            amount: Uint128::from(20u32),
+            msg: to_binary(&ReceiveMsg::AddToBucketCw20 {
+                bucket_id: 1,
+            })?,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Sam add to bucket", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Buy & settle
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let buy_and_settle = ExecuteMsg::BuyListing {
+        listing_id: 1,
+        ask_index: 0,
+        bucket_id: 1,
+        settle: true,
+    };
+    let pause = |withdraw: bool| ExecuteMsg::SetPause {
+        pause: Pause {
+            create: false,
+            buy: false,
+            withdraw,
+        },
+    };
+
+    // Settling pays out, so it's blocked while withdraws are paused
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &pause(true),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin pause withdraw", line!(), column!()));
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), fuzionmarket.clone(), &buy_and_settle, &[]);
+    ensure!(res.is_err(), here("Sam buy & settle while withdraw paused", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &pause(false),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin unpause withdraw", line!(), column!()));
+
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), fuzionmarket.clone(), &buy_and_settle, &[]);
+    ensure!(res.is_ok(), here("Sam buy & settle", line!(), column!()));
+
+    // Both records are gone
+    let res: Result<crate::query::ListingResponse, _> = router.wrap().query_wasm_smart(
+        fuzionmarket.clone(),
+        &QueryMsg::GetListing {
+            listing_id: 1,
+        },
+    );
+    ensure!(res.is_err(), here("Listing still stored", line!(), column!()));
+    for owner in [&john, &sam] {
+        let res: Result<crate::query::BucketResponse, _> = router.wrap().query_wasm_smart(
+            fuzionmarket.clone(),
+            &QueryMsg::GetBucket {
+                owner: owner.address.to_string(),
+                bucket_id: 1,
+            },
+        );
+        ensure!(res.is_err(), here("Bucket still stored", line!(), column!()));
+    }
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::WithdrawPurchased {
+            listing_id: 1,
+        },
+        &[],
+    );
+    ensure!(res.is_err(), here("Sam withdraw settled listing", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Balances
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let bal = |addr: &str| router.wrap().query_balance(addr, VALID_NATIVE).map(|c| c.amount);
+
+    // Sam: 100_000_000 - 1_000 + (1_000_000 - 0.5% fee of 5_000)
+    assert_eq!(bal(sam.address.as_str())?, Uint128::from(100_994_000_u32));
+    // John: 100_000_000 - 1_000_000 + (1_000 - 0.5% fee of 5)
+    assert_eq!(bal(john.address.as_str())?, Uint128::from(99_000_995_u32));
+    assert_eq!(jvtwo.balance(&router.wrap(), john.address.clone())?, Uint128::from(120u32));
+    assert_eq!(bal(COMMUNITY_POOL)?, Uint128::from(5_005_u32));
+    assert_eq!(bal(fuzionmarket.as_str())?, Uint128::zero());
+
+    Ok(())
+}
+
+// Withdraw from Bucket
+// <X> Only the owner can withdraw, and only what the Bucket holds
+// <X> Partial withdrawals keep the Bucket, withdrawing everything removes it
+// <X> Bucket used to buy a Listing can only be fully withdrawn, paying the fee
+#[test]
+fn withdraw_from_bucket() -> Result<(), anyhow::Error> {
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, jvtwo, jvtre, _neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // John's bucket: 5_000 JUNO + 50 JVTWO
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateBucket {},
+        &coins(5_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John create bucket", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        jvtwo.addr(),
+        &cw20_base::msg::ExecuteMsg::Send {
+            contract: fuzionmarket.to_string(),
+            amount: Uint128::from(50u32),
+            msg: to_binary(&ReceiveMsg::AddToBucketCw20 {
+                bucket_id: 1,
+            })?,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John add to bucket", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Invalid withdrawals
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let invalid = [
+        // More than the bucket holds
+        GenericBalance {
+            native: coins(5_001, VALID_NATIVE),
+            cw20: vec![],
+            nfts: vec![],
+        },
+        // Token not in the bucket
+        GenericBalance {
+            native: vec![],
+            cw20: vec![Cw20CoinVerified {
+                address: jvtre.addr(),
+                amount: Uint128::from(1u32),
+            }],
+            nfts: vec![],
+        },
+        // 0 amount
+        GenericBalance {
+            native: coins(0, VALID_NATIVE),
+            cw20: vec![],
+            nfts: vec![],
+        },
+        // Nothing
+        GenericBalance {
+            native: vec![],
+            cw20: vec![],
+            nfts: vec![],
+        },
+    ];
+    for assets in invalid {
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &ExecuteMsg::WithdrawFromBucket {
+                bucket_id: 1,
+                assets,
+            },
+            &[],
+        );
+        ensure!(res.is_err(), here("John invalid withdraw", line!(), column!()));
+    }
+
+    // Sam can't withdraw from John's bucket
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::WithdrawFromBucket {
+            bucket_id: 1,
+            assets: GenericBalance {
+                native: coins(1, VALID_NATIVE),
+                cw20: vec![],
+                nfts: vec![],
+            },
+        },
+        &[],
+    );
+    ensure!(res.is_err(), here("Sam withdraw John's bucket", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Partial withdrawal
+    // 2_000 JUNO + 20 JVTWO
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::WithdrawFromBucket {
+            bucket_id: 1,
+            assets: GenericBalance {
+                native: coins(2_000, VALID_NATIVE),
+                cw20: vec![Cw20CoinVerified {
+                    address: jvtwo.addr(),
+                    amount: Uint128::from(20u32),
+                }],
+                nfts: vec![],
+            },
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John partial withdraw", line!(), column!()));
+
+    let res: crate::query::BucketResponse = router.wrap().query_wasm_smart(
+        fuzionmarket.clone(),
+        &QueryMsg::GetBucket {
+            owner: john.address.to_string(),
+            bucket_id: 1,
+        },
+    )?;
+    assert_eq!(res.bucket.funds.native, coins(3_000, VALID_NATIVE));
+    assert_eq!(res.bucket.funds.cw20[0].amount, Uint128::from(30u32));
+
+    let bal = |addr: &str| router.wrap().query_balance(addr, VALID_NATIVE).map(|c| c.amount);
+    assert_eq!(bal(john.address.as_str())?, Uint128::from(99_997_000_u32));
+    assert_eq!(jvtwo.balance(&router.wrap(), john.address.clone())?, Uint128::from(70u32));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Withdrawing everything removes the bucket
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateBucket {},
+        &coins(100, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John create bucket 2", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::WithdrawFromBucket {
+            bucket_id: 2,
+            assets: GenericBalance {
+                native: coins(100, VALID_NATIVE),
+                cw20: vec![],
+                nfts: vec![],
+            },
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John withdraw all", line!(), column!()));
+    let res: Result<crate::query::BucketResponse, _> = router.wrap().query_wasm_smart(
+        fuzionmarket.clone(),
+        &QueryMsg::GetBucket {
+            owner: john.address.to_string(),
+            bucket_id: 2,
+        },
+    );
+    ensure!(res.is_err(), here("Empty bucket still stored", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Bucket used to buy a Listing can't be partially withdrawn
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateListing {
+            create_msg: CreateListingMsg {
+                asks: vec![GenericBalance {
+                    native: coins(3_000, VALID_NATIVE),
+                    cw20: vec![Cw20CoinVerified {
+                        address: jvtwo.addr(),
+                        amount: Uint128::from(30u32),
+                    }],
+                    nfts: vec![],
+                }],
+                buyer_restriction: None,
+            },
+        },
+        &coins(1_000_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("Sam create listing", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::Finalize {
+            listing_id: 1,
+            expiration: expires_in(router, 1000),
+            start: None,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Sam finalize", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::BuyListing {
+            listing_id: 1,
+            ask_index: 0,
+            bucket_id: 1,
+            settle: false,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John buy", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::WithdrawFromBucket {
+            bucket_id: 1,
+            assets: GenericBalance {
+                native: coins(1_000, VALID_NATIVE),
+                cw20: vec![],
+                nfts: vec![],
+            },
+        },
+        &[],
+    );
+    ensure!(res.is_err(), here("Sam partial withdraw committed", line!(), column!()));
+
+    // Full withdrawal still pays out the fee
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::RemoveBucket {
+            bucket_id: 1,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Sam remove bucket", line!(), column!()));
+
+    Ok(())
+}
+
+// Remove from Listing
+// <X> Can't remove more than the Listing holds, nothing, or everything
+// <X> Only the creator can remove
+// <X> Can't remove once finalized
+#[test]
+fn remove_from_listing() -> Result<(), anyhow::Error> {
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Listing
+    // FOR_SALE: 10_000 JUNO + NeonPeepz 1
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateListing {
+            create_msg: create_valid_listing::create_listing_msg(
+                jvone.addr(),
+                neonpeepz.addr(),
+                None,
+            ),
+        },
+        &coins(10_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        neonpeepz.addr(),
+        &cw721_base::ExecuteMsg::<Option<Empty>, Empty>::SendNft {
+            contract: fuzionmarket.to_string(),
+            token_id: "1".to_string(),
+            msg: to_binary(&crate::msg::ReceiveNftMsg::AddToListingCw721 {
+                listing_id: 1,
+            })?,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John add NFT", line!(), column!()));
+
+    let remove = |native: u128, nft: bool| ExecuteMsg::RemoveFromListing {
+        listing_id: 1,
+        assets: GenericBalance {
+            native: if native > 0 {
+                coins(native, VALID_NATIVE)
+            } else {
+                vec![]
+            },
+            cw20: vec![],
+            nfts: if nft {
+                vec![Nft {
+                    contract_address: neonpeepz.addr(),
+                    token_id: "1".to_string(),
+                }]
+            } else {
+                vec![]
+            },
+        },
+    };
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Invalid removals
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // More than the listing holds
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &remove(10_001, false),
+        &[],
+    );
+    ensure!(res.is_err(), here("John remove too much", line!(), column!()));
+
+    // Nothing
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), fuzionmarket.clone(), &remove(0, false), &[]);
+    ensure!(res.is_err(), here("John remove nothing", line!(), column!()));
+
+    // Everything
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &remove(10_000, true),
+        &[],
+    );
+    ensure!(res.is_err(), here("John remove everything", line!(), column!()));
+
+    // Not the creator
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &remove(1_000, false),
+        &[],
+    );
+    ensure!(res.is_err(), here("Sam remove from John's listing", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Remove 4_000 JUNO + NeonPeepz 1
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &remove(4_000, true),
+        &[],
+    );
+    ensure!(res.is_ok(), here("John remove", line!(), column!()));
+
+    let res: crate::query::ListingResponse = router.wrap().query_wasm_smart(
+        fuzionmarket.clone(),
+        &QueryMsg::GetListing {
+            listing_id: 1,
+        },
+    )?;
+    assert_eq!(res.listing.for_sale.native, coins(6_000, VALID_NATIVE));
+    assert!(res.listing.for_sale.nfts.is_empty());
+
+    let owner = neonpeepz.owner_of(&router.wrap(), "1".to_string(), false)?.owner;
+    assert_eq!(owner, john.address.to_string());
+    let bal = router.wrap().query_balance(john.address.as_str(), VALID_NATIVE)?.amount;
+    assert_eq!(bal, Uint128::from(99_994_000_u32));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Can't remove once finalized
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::Finalize {
+            listing_id: 1,
+            expiration: expires_in(router, 1000),
+            start: None,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John finalize", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &remove(1_000, false),
+        &[],
+    );
+    ensure!(res.is_err(), here("John remove after finalize", line!(), column!()));
+
+    Ok(())
+}
+
+// Cancel Listing
+// <X> Minimum live time before cancelling, however large it's set
+// <X> Unfinalized & expired Listings can't be cancelled
+// <X> Cancel fee is charged when enabled, except on NFT only Listings
+#[test]
+fn cancel_listing() -> Result<(), anyhow::Error> {
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+
+    // John lists 1_000_000 JUNO, finalizing if `finalize`
+    let list = |router: &mut App, listing_id: u64, finalize: bool| -> Result<()> {
+        router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &ExecuteMsg::CreateListing {
+                create_msg: create_valid_listing::create_listing_msg(
+                    jvone.addr(),
+                    neonpeepz.addr(),
+                    None,
+                ),
+            },
+            &coins(1_000_000, VALID_NATIVE),
+        )?;
+        if finalize {
+            router.execute_contract(
+                john.address.clone(),
+                fuzionmarket.clone(),
+                &ExecuteMsg::Finalize {
+                    listing_id,
+                    expiration: expires_in(router, 1000),
+                    start: None,
+                },
+                &[],
+            )?;
+        }
+        Ok(())
+    };
+    let cancel = |listing_id: u64| ExecuteMsg::CancelListing {
+        listing_id,
+    };
+    let bal = |router: &App, addr: &str| {
+        router.wrap().query_balance(addr, VALID_NATIVE).map(|c| c.amount)
+    };
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Default: 600 second minimum, no fee
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    list(router, 1, true)?;
+
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), fuzionmarket.clone(), &cancel(1), &[]);
+    ensure!(res.is_err(), here("John cancel too early", line!(), column!()));
+
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 120;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(600);
+    });
+
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), fuzionmarket.clone(), &cancel(1), &[]);
+    ensure!(res.is_err(), here("Sam cancel John's listing", line!(), column!()));
+
+    let res: AppResponse =
+        router.execute_contract(john.address.clone(), fuzionmarket.clone(), &cancel(1), &[])?;
+    ensure!(
+        res.events.iter().any(|e| e.ty == "wasm-listing_cancelled"),
+        here("No cancel event", line!(), column!())
+    );
+    assert_eq!(bal(router, john.address.as_str())?, Uint128::from(100_000_000_u32));
+
+    let res: Result<crate::query::ListingResponse, _> = router.wrap().query_wasm_smart(
+        fuzionmarket.clone(),
+        &QueryMsg::GetListing {
+            listing_id: 1,
+        },
+    );
+    ensure!(res.is_err(), here("Cancelled listing still stored", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Not finalized or expired, use DeleteListing
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    list(router, 2, false)?;
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), fuzionmarket.clone(), &cancel(2), &[]);
+    ensure!(res.is_err(), here("John cancel unfinalized", line!(), column!()));
+
+    list(router, 3, true)?;
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 200;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(1001);
+    });
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), fuzionmarket.clone(), &cancel(3), &[]);
+    ensure!(res.is_err(), here("John cancel expired", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Minimum past the end of time
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::UpdateConfig {
+            fee_recipient: None,
+            limits: Some(Limits {
+                min_cancel_seconds: u64::MAX,
+                ..Limits::default()
+            }),
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin update config", line!(), column!()));
+
+    list(router, 4, true)?;
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), fuzionmarket.clone(), &cancel(4), &[]);
+    ensure!(res.is_err(), here("John cancel with max minimum", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Fee, no minimum
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::UpdateConfig {
+            fee_recipient: None,
+            limits: Some(Limits {
+                min_cancel_seconds: 0,
+                cancel_fee: true,
+                ..Limits::default()
+            }),
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin update config", line!(), column!()));
+
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), fuzionmarket.clone(), &cancel(4), &[]);
+    ensure!(res.is_ok(), here("John cancel with fee", line!(), column!()));
+
+    // John: 100_000_000 - 2 x 1_000_000 still listed - 0.5% fee of 5_000
+    assert_eq!(bal(router, john.address.as_str())?, Uint128::from(97_995_000_u32));
+    assert_eq!(bal(router, COMMUNITY_POOL)?, Uint128::from(5_000_u32));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Fee, NFT only Listing isn't charged
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        neonpeepz.addr(),
+        &cw721_base::msg::ExecuteMsg::<Option<Empty>, Empty>::SendNft {
+            contract: fuzionmarket.to_string(),
+            token_id: "1".to_string(),
+            msg: to_binary(&crate::msg::ReceiveNftMsg::CreateListingCw721 {
+                create_msg: create_valid_listing::create_listing_msg(
+                    jvone.addr(),
+                    neonpeepz.addr(),
+                    None,
+                ),
+            })?,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John create NFT listing", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::Finalize {
+            listing_id: 5,
+            expiration: expires_in(router, 1000),
+            start: None,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John finalize NFT listing", line!(), column!()));
+
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), fuzionmarket.clone(), &cancel(5), &[]);
+    ensure!(res.is_ok(), here("John cancel NFT listing", line!(), column!()));
+
+    let owner = neonpeepz.owner_of(&router.wrap(), "1".to_string(), false)?.owner;
+    assert_eq!(owner, john.address.to_string());
+    assert_eq!(bal(router, COMMUNITY_POOL)?, Uint128::from(5_000_u32));
+
+    Ok(())
+}
+
+// Relist & Extend Expiration
+// <X> Live Listings can be extended, only later & within the limits
+// <X> Expired Listings can be relisted, with a valid new ask
+// <X> Only the creator can relist or extend
+#[test]
+fn relist_and_extend() -> Result<(), anyhow::Error> {
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Listing, live for 1000 seconds
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateListing {
+            create_msg: create_valid_listing::create_listing_msg(
+                jvone.addr(),
+                neonpeepz.addr(),
+                None,
+            ),
+        },
+        &coins(1_000_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::Finalize {
+            listing_id: 1,
+            expiration: expires_in(router, 1000),
+            start: None,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John finalize", line!(), column!()));
+
+    let extend = |expiration: Expiration| ExecuteMsg::ExtendExpiration {
+        listing_id: 1,
+        expiration,
+    };
+    let relist = |expiration: Expiration, new_ask: Option<GenericBalance>| ExecuteMsg::Relist {
+        listing_id: 1,
+        expiration,
+        new_asks: new_ask.map(|ask| vec![ask]),
+    };
+    let get_listing = |router: &App| -> Result<crate::query::ListingResponse> {
+        Ok(router.wrap().query_wasm_smart(
+            fuzionmarket.clone(),
+            &QueryMsg::GetListing {
+                listing_id: 1,
+            },
+        )?)
+    };
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Extend while live
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &relist(expires_in(router, 1000), None),
+        &[],
+    );
+    ensure!(res.is_err(), here("John relist live listing", line!(), column!()));
+
+    // Earlier than current expiration
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &extend(expires_in(router, 600)),
+        &[],
+    );
+    ensure!(res.is_err(), here("John shorten listing", line!(), column!()));
+
+    // Over the maximum
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &extend(expires_in(router, 1_209_601)),
+        &[],
+    );
+    ensure!(res.is_err(), here("John extend too long", line!(), column!()));
+
+    // Not the creator
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &extend(expires_in(router, 2000)),
+        &[],
+    );
+    ensure!(res.is_err(), here("Sam extend John's listing", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &extend(expires_in(router, 2000)),
+        &[],
+    );
+    ensure!(res.is_ok(), here("John extend", line!(), column!()));
+
+    let now = router.block_info().time;
+    let res = get_listing(router)?;
+    assert_eq!(res.listing.expiration, Some(Expiration::AtTime(now.plus_seconds(2000))));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Relist once expired
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 400;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(2001);
+    });
+    ensure!(!get_listing(router)?.purchasable, here("Listing not expired", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &extend(expires_in(router, 2000)),
+        &[],
+    );
+    ensure!(res.is_err(), here("John extend expired listing", line!(), column!()));
+
+    // Under the minimum
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &relist(expires_in(router, 599), None),
+        &[],
+    );
+    ensure!(res.is_err(), here("John relist too short", line!(), column!()));
+
+    // Invalid ask
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &relist(
+            expires_in(router, 1000),
+            Some(GenericBalance {
+                native: coins(0, VALID_NATIVE),
+                cw20: vec![],
+                nfts: vec![],
+            }),
+        ),
+        &[],
+    );
+    ensure!(res.is_err(), here("John relist invalid ask", line!(), column!()));
+
+    let new_ask = GenericBalance {
+        native: coins(5, VALID_NATIVE),
+        cw20: vec![],
+        nfts: vec![],
+    };
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &relist(expires_in(router, 1000), Some(new_ask.clone())),
+        &[],
+    );
+    ensure!(res.is_ok(), here("John relist", line!(), column!()));
+
+    let now = router.block_info().time;
+    let res = get_listing(router)?;
+    ensure!(res.purchasable, here("Relisted listing not purchasable", line!(), column!()));
+    assert_eq!(res.listing.asks, vec![new_ask]);
+    assert_eq!(res.listing.for_sale.native, coins(1_000_000, VALID_NATIVE));
+    assert_eq!(res.listing.finalized_time, Some(now));
+    assert_eq!(res.listing.expiration, Some(Expiration::AtTime(now.plus_seconds(1000))));
+
+    Ok(())
+}
+
+// Expiration Limits
+// <X> Default & configured bounds, by time & by height
+// <X> SoonestExpiring sorts time, then height, then Never
+// <X> Never expiring Listings can't be deleted
+// <X> Extending keeps the kind of expiration, or moves to Never
+#[test]
+fn expiration_limits() -> Result<(), anyhow::Error> {
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+
+    // John creates 4 listings
+    for _ in 0..4 {
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &ExecuteMsg::CreateListing {
+                create_msg: create_valid_listing::create_listing_msg(
+                    jvone.addr(),
+                    neonpeepz.addr(),
+                    None,
+                ),
+            },
+            &coins(1_000, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+    }
+
+    let finalize = |listing_id: u64, expiration: Expiration| ExecuteMsg::Finalize {
+        listing_id,
+        expiration,
+        start: None,
+    };
+    let height = |router: &App| router.block_info().height;
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Default limits
+    // 100..=201_600 blocks, no Never
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &finalize(1, Expiration::AtHeight(height(router) + 99)),
+        &[],
+    );
+    ensure!(res.is_err(), here("Height too soon", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &finalize(1, Expiration::AtHeight(height(router) + 201_601)),
+        &[],
+    );
+    ensure!(res.is_err(), here("Height too late", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &finalize(1, Expiration::Never {}),
+        &[],
+    );
+    ensure!(res.is_err(), here("Never not allowed", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &finalize(1, Expiration::AtHeight(height(router) + 100)),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Finalize at height", line!(), column!()));
+
+    // Expires at height, not time
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 100;
+    });
+    let res: crate::query::ListingResponse = router.wrap().query_wasm_smart(
+        fuzionmarket.clone(),
+        &QueryMsg::GetListing {
+            listing_id: 1,
+        },
+    )?;
+    ensure!(!res.purchasable, here("Height listing not expired", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::DeleteListing {
+            listing_id: 1,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Delete expired height listing", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Configured limits
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let update = |expiration: ExpirationLimits| ExecuteMsg::UpdateConfig {
+        fee_recipient: None,
+        limits: Some(Limits {
+            expiration,
+            ..Limits::default()
+        }),
+    };
+
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &update(ExpirationLimits {
+            min_seconds: 61,
+            max_seconds: 60,
+            ..ExpirationLimits::default()
+        }),
+        &[],
+    );
+    ensure!(res.is_err(), here("Min over max", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &update(ExpirationLimits {
+            min_seconds: 60,
+            max_seconds: 31_536_000,
+            min_blocks: 10,
+            max_blocks: 1_000_000,
+            allow_never: true,
+        }),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin update limits", line!(), column!()));
+
+    let expirations = [
+        (2, expires_in(router, 60)),
+        (3, Expiration::Never {}),
+        (4, Expiration::AtHeight(height(router) + 10)),
+    ];
+    for (listing_id, expiration) in expirations {
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &finalize(listing_id, expiration),
+            &[],
+        );
+        ensure!(res.is_ok(), here("Finalize with new limits", line!(), column!()));
+    }
+
+    // Time based, then height based, then Never
+    let res: crate::query::MarketListingsResponse = router.wrap().query_wasm_smart(
+        fuzionmarket.clone(),
+        &QueryMsg::GetListingsForMarket {
+            filter: None,
+            sort: Some(crate::query::MarketSort::SoonestExpiring),
+            start_after: None,
+            limit: None,
+        },
+    )?;
+    assert_eq!(res.listings.iter().map(|l| l.id).collect::<Vec<u64>>(), vec![2, 4, 3]);
+
+    // Never expiring listings can't be deleted
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::DeleteListing {
+            listing_id: 3,
+        },
+        &[],
+    );
+    ensure!(res.is_err(), here("Delete Never listing", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Extending keeps the kind of expiration, or moves to Never
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let extend = |listing_id: u64, expiration: Expiration| ExecuteMsg::ExtendExpiration {
+        listing_id,
+        expiration,
+    };
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &extend(2, Expiration::AtHeight(height(router) + 1000)),
+        &[],
+    );
+    ensure!(res.is_err(), here("Extend time to height", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &extend(3, expires_in(router, 1000)),
+        &[],
+    );
+    ensure!(res.is_err(), here("Extend Never to time", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &extend(4, Expiration::AtHeight(height(router) + 1000)),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Extend height", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &extend(2, Expiration::Never {}),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Extend time to Never", line!(), column!()));
+
+    Ok(())
+}
+
+// Scheduled Start
+// <X> Start must be in the future, before expiration & the same kind
+// <X> Can't buy before start, upcoming Listings are browsed separately
+// <X> Relist clears the start
+#[test]
+fn scheduled_start() -> Result<(), anyhow::Error> {
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // 2 Listings
+    // FOR_SALE: 10_000 JUNO
+    // PRICE: 1_000 JUNO
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    for _ in 0..2 {
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &ExecuteMsg::CreateListing {
+                create_msg: CreateListingMsg {
+                    asks: vec![GenericBalance {
+                        native: coins(1_000, VALID_NATIVE),
+                        cw20: vec![],
+                        nfts: vec![],
+                    }],
+                    buyer_restriction: None,
+                },
+            },
+            &coins(10_000, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+    }
+
+    let now = router.block_info().time;
+    let finalize = |listing_id: u64, start: Option<Scheduled>| ExecuteMsg::Finalize {
+        listing_id,
+        expiration: Expiration::AtTime(now.plus_seconds(1000)),
+        start,
+    };
+
+    // Start must be in the future, before expiration & the same kind as it
+    for start in [
+        Scheduled::AtTime(now),
+        Scheduled::AtTime(now.plus_seconds(1000)),
+        Scheduled::AtHeight(router.block_info().height + 10),
+    ] {
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &finalize(1, Some(start)),
+            &[],
+        );
+        ensure!(res.is_err(), here("John finalize invalid start", line!(), column!()));
+    }
+
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &finalize(1, Some(Scheduled::AtTime(now.plus_seconds(300)))),
+        &[],
+    );
+    ensure!(res.is_ok(), here("John finalize with start", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &finalize(2, None),
+        &[],
+    );
+    ensure!(res.is_ok(), here("John finalize", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Before start
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let buy = ExecuteMsg::BuyListingWithFunds {
+        listing_id: 1,
+        ask_index: 0,
+    };
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &buy,
+        &coins(1_000, VALID_NATIVE),
+    );
+    let err = res.expect_err(&here("Sam buy before start", line!(), column!()));
+    ensure!(
+        err.root_cause().to_string().contains("can't be bought until"),
+        here("Expected NotStarted", line!(), column!())
+    );
+
+    let res: crate::query::ListingResponse = router.wrap().query_wasm_smart(
+        fuzionmarket.clone(),
+        &QueryMsg::GetListing {
+            listing_id: 1,
+        },
+    )?;
+    ensure!(!res.purchasable, here("Upcoming listing purchasable", line!(), column!()));
+
+    // Live & upcoming listings are browsed separately
+    let browse = |router: &App, upcoming: bool| -> Result<Vec<u64>> {
+        let res: crate::query::MarketListingsResponse = router.wrap().query_wasm_smart(
+            fuzionmarket.clone(),
+            &QueryMsg::GetListingsForMarket {
+                filter: Some(crate::query::MarketFilter {
+                    upcoming,
+                    ..Default::default()
+                }),
+                sort: None,
+                start_after: None,
+                limit: None,
+            },
+        )?;
+        Ok(res.listings.iter().map(|l| l.id).collect())
+    };
+    assert_eq!(browse(router, false)?, vec![2]);
+    assert_eq!(browse(router, true)?, vec![1]);
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // After start
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 60;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(300);
+    });
+    assert_eq!(browse(router, false)?, vec![2, 1]);
+    assert!(browse(router, true)?.is_empty());
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &buy,
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("Sam buy after start", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Relist clears the start
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateListing {
+            create_msg: CreateListingMsg {
+                asks: vec![GenericBalance {
+                    native: coins(1_000, VALID_NATIVE),
+                    cw20: vec![],
+                    nfts: vec![],
+                }],
+                buyer_restriction: None,
+            },
+        },
+        &coins(10_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John create listing 3", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &finalize(3, Some(Scheduled::AtTime(now.plus_seconds(600)))),
+        &[],
+    );
+    ensure!(res.is_ok(), here("John finalize listing 3 with start", line!(), column!()));
+
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 200;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(800);
+    });
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::Relist {
+            listing_id: 3,
+            expiration: expires_in(router, 1000),
+            new_asks: None,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John relist listing 3", line!(), column!()));
+
+    let res: crate::query::ListingResponse = router.wrap().query_wasm_smart(
+        fuzionmarket,
+        &QueryMsg::GetListing {
+            listing_id: 3,
+        },
+    )?;
+    assert_eq!(res.listing.start, None);
+    ensure!(res.purchasable, here("Relisted listing not purchasable", line!(), column!()));
+
+    Ok(())
+}
+
+// Buyer Restrictions
+// <X> Invalid restrictions are rejected
+// <X> Address lists, cw4 groups & NFT holders are enforced when buying
+// <X> Only address lists are reachable through GetWhitelistedListings
+#[test]
+fn buyer_restrictions() -> Result<(), anyhow::Error> {
+    use crate::query::MultiListingResponse;
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+    let badguy = create_users::fake_user("badguy".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Max is the only member of the group
+    let group_id = router.store_code(create_contract::cw4_group_contract());
+    let group = router.instantiate_contract(
+        group_id,
+        contract_admin.address.clone(),
+        &create_contract::Cw4GroupInstantiateMsg {
+            members: vec![max.address.to_string()],
+        },
+        &[],
+        "cw4 group",
+        None,
+    )?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    let router = give_natives(&max, router);
+    let router = give_natives(&badguy, router);
+
+    let create = |buyer_restriction: BuyerRestriction| ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            asks: vec![GenericBalance {
+                native: coins(1_000, VALID_NATIVE),
+                cw20: vec![],
+                nfts: vec![],
+            }],
+            buyer_restriction: Some(buyer_restriction),
+        },
+    };
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Invalid restrictions
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let too_many = (0..51).map(|i| Addr::unchecked(format!("buyer{}", i))).collect();
+    let invalid = [
+        BuyerRestriction::Addresses(vec![]),
+        BuyerRestriction::Addresses(vec![sam.address.clone(), sam.address.clone()]),
+        BuyerRestriction::Addresses(too_many),
+        BuyerRestriction::Cw4Group(Addr::unchecked("")),
+        BuyerRestriction::NftHolder(Addr::unchecked("")),
+    ];
+    for restriction in invalid {
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &create(restriction),
+            &coins(1_000, VALID_NATIVE),
+        );
+        ensure!(res.is_err(), here("John create invalid restriction", line!(), column!()));
+    }
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Listings, all asking 1_000 JUNO
+    // 1 | Sam or Max
+    // 2 | group members (Max)
+    // 3 | Neon Peepz holders (Sam & Max)
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let restrictions = [
+        BuyerRestriction::Addresses(vec![sam.address.clone(), max.address.clone()]),
+        BuyerRestriction::Cw4Group(group),
+        BuyerRestriction::NftHolder(neonpeepz.addr()),
+    ];
+    for (id, restriction) in restrictions.into_iter().enumerate() {
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &create(restriction),
+            &coins(1_000, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &ExecuteMsg::Finalize {
+                listing_id: id as u64 + 1,
+                expiration: expires_in(router, 1000),
+                start: None,
+            },
+            &[],
+        );
+        ensure!(res.is_ok(), here("John finalize", line!(), column!()));
+    }
+
+    // Only address lists are reachable through GetWhitelistedListings
+    for buyer in [&sam, &max] {
+        let res: MultiListingResponse = router.wrap().query_wasm_smart(
+            fuzionmarket.clone(),
+            &QueryMsg::GetWhitelistedListings {
+                buyer: buyer.address.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )?;
+        ensure!(
+            res.listings.iter().map(|l| l.id).eq([1]),
+            here("Whitelisted listings", line!(), column!())
+        );
+    }
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Buying
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let buys = [
+        (1, &badguy, false),
+        (1, &sam, true),
+        (2, &sam, false),
+        (2, &max, true),
+        (3, &badguy, false),
+        (3, &sam, true),
+    ];
+    for (listing_id, buyer, allowed) in buys {
+        let res: Result<AppResponse> = router.execute_contract(
+            buyer.address.clone(),
+            fuzionmarket.clone(),
+            &ExecuteMsg::BuyListingWithFunds {
+                listing_id,
+                ask_index: 0,
+            },
+            &coins(1_000, VALID_NATIVE),
+        );
+        if allowed {
+            ensure!(res.is_ok(), here("Allowed buyer", line!(), column!()));
+        } else {
+            let err = res.expect_err(&here("Restricted buyer", line!(), column!()));
+            ensure!(
+                err.root_cause().to_string().contains("Not whitelisted"),
+                here("Expected NotWhitelisted", line!(), column!())
+            );
+        }
+    }
+
+    Ok(())
+}
+
+// Alternative Asks
+// <X> Invalid asks are rejected
+// <X> Every ask is shown
+// <X> Payment must match the ask at ask_index, from funds or a Bucket
+#[test]
+fn alternative_asks() -> Result<(), anyhow::Error> {
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, jvtwo, _jvtre, _neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let juno_ask = GenericBalance {
+        native: coins(1_000, VALID_NATIVE),
+        cw20: vec![],
+        nfts: vec![],
+    };
+    let jvtwo_ask = GenericBalance {
+        native: vec![],
+        cw20: vec![Cw20CoinVerified {
+            address: jvtwo.addr(),
+            amount: Uint128::from(20u32),
+        }],
+        nfts: vec![],
+    };
+    let create = |asks: Vec<GenericBalance>| ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            asks,
+            buyer_restriction: None,
+        },
+    };
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Invalid asks
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let zero_ask = GenericBalance {
+        native: coins(0, VALID_NATIVE),
+        cw20: vec![],
+        nfts: vec![],
+    };
+    let invalid = [vec![], vec![juno_ask.clone(); 11], vec![juno_ask.clone(), zero_ask]];
+    for asks in invalid {
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &create(asks),
+            &coins(10_000, VALID_NATIVE),
+        );
+        ensure!(res.is_err(), here("John create invalid asks", line!(), column!()));
+    }
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // 2 Listings
+    // FOR_SALE: 10_000 JUNO
+    // ASKS: 1_000 JUNO | 20 JVTWO
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    for listing_id in [1, 2] {
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &create(vec![juno_ask.clone(), jvtwo_ask.clone()]),
+            &coins(10_000, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &ExecuteMsg::Finalize {
+                listing_id,
+                expiration: expires_in(router, 1000),
+                start: None,
+            },
+            &[],
+        );
+        ensure!(res.is_ok(), here("John finalize", line!(), column!()));
+    }
+
+    // Every option is shown
+    let res: crate::query::ListingResponse = router.wrap().query_wasm_smart(
+        fuzionmarket.clone(),
+        &QueryMsg::GetListing {
+            listing_id: 1,
+        },
+    )?;
+    assert_eq!(res.listing.asks, vec![juno_ask.clone(), jvtwo_ask]);
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Funds must match the named ask
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let buy_with_funds = |ask_index: u32| ExecuteMsg::BuyListingWithFunds {
+        listing_id: 1,
+        ask_index,
+    };
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &buy_with_funds(1),
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_err(), here("Sam pay JUNO for JVTWO ask", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &buy_with_funds(2),
+        &coins(1_000, VALID_NATIVE),
+    );
+    let err = res.expect_err(&here("Sam buy missing ask", line!(), column!()));
+    ensure!(
+        err.root_cause().to_string().contains("no ask at index 2"),
+        here("Expected InvalidAskIndex", line!(), column!())
+    );
+
+    // Second option, paid in JVTWO
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        jvtwo.addr(),
+        &cw20_base::msg::ExecuteMsg::Send {
+            contract: fuzionmarket.to_string(),
+            amount: Uint128::from(20u32),
+            msg: to_binary(&ReceiveMsg::BuyListingCw20 {
+                listing_id: 1,
+                ask_index: 1,
+            })?,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Sam buy listing 1 with JVTWO", line!(), column!()));
+    assert_eq!(jvtwo.balance(&router.wrap(), john.address.clone())?, Uint128::from(120u32));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // First option, paid from a Bucket
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateBucket {},
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("Sam create bucket", line!(), column!()));
+
+    let buy = |ask_index: u32| ExecuteMsg::BuyListing {
+        listing_id: 2,
+        bucket_id: 1,
+        ask_index,
+        settle: true,
+    };
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), fuzionmarket.clone(), &buy(1), &[]);
+    ensure!(res.is_err(), here("Sam bucket doesn't match ask 1", line!(), column!()));
+
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), fuzionmarket.clone(), &buy(0), &[]);
+    ensure!(res.is_ok(), here("Sam buy listing 2 with bucket", line!(), column!()));
+
+    Ok(())
+}
+
+// Buy with Surplus
+// <X> Bucket must hold at least the ask
+// <X> Unsettled buys move the surplus to a new Bucket
+// <X> Settled buys leave the surplus in the same Bucket
+#[test]
+fn buy_with_surplus() -> Result<(), anyhow::Error> {
+    use crate::query::BucketResponse;
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // 2 Listings
+    // FOR_SALE: 10_000 JUNO
+    // PRICE: 1_000 JUNO
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    for listing_id in [1, 2] {
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &ExecuteMsg::CreateListing {
+                create_msg: CreateListingMsg {
+                    asks: vec![GenericBalance {
+                        native: coins(1_000, VALID_NATIVE),
+                        cw20: vec![],
+                        nfts: vec![],
+                    }],
+                    buyer_restriction: None,
+                },
+            },
+            &coins(10_000, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &ExecuteMsg::Finalize {
+                listing_id,
+                expiration: expires_in(router, 1000),
+                start: None,
+            },
+            &[],
+        );
+        ensure!(res.is_ok(), here("John finalize", line!(), column!()));
+    }
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Buckets
+    // 1 | 999 JUNO
+    // 2 | 1_500 JUNO
+    // 3 | 2_000 JUNO
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    for amount in [999, 1_500, 2_000] {
+        let res: Result<AppResponse> = router.execute_contract(
+            sam.address.clone(),
+            fuzionmarket.clone(),
+            &ExecuteMsg::CreateBucket {},
+            &coins(amount, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here("Sam create bucket", line!(), column!()));
+    }
+
+    let buy = |listing_id: u64, bucket_id: u64, settle: bool| ExecuteMsg::BuyListing {
+        listing_id,
+        bucket_id,
+        ask_index: 0,
+        settle,
+    };
+    let get_bucket = |router: &App, owner: &User, bucket_id: u64| {
+        router.wrap().query_wasm_smart::<BucketResponse>(
+            fuzionmarket.clone(),
+            &QueryMsg::GetBucket {
+                owner: owner.address.to_string(),
+                bucket_id,
+            },
+        )
+    };
+
+    // Short of the ask
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), fuzionmarket.clone(), &buy(1, 1, false), &[]);
+    ensure!(res.is_err(), here("Sam buy with short bucket", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Not settled, surplus moves to a new Bucket
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), fuzionmarket.clone(), &buy(1, 2, false), &[]);
+    let res = res.unwrap_or_else(|_| panic!("{}", here("Sam buy listing 1", line!(), column!())));
+    ensure!(
+        res.events
+            .iter()
+            .flat_map(|e| &e.attributes)
+            .any(|a| a.key == "surplus_bucket" && a.value == "4"),
+        here("Expected surplus bucket 4", line!(), column!())
+    );
+
+    let seller = get_bucket(router, &john, 2)?.bucket;
+    assert_eq!(seller.funds.native, coins(995, VALID_NATIVE));
+    assert_eq!(seller.paid_for, Some(1));
+    let surplus = get_bucket(router, &sam, 4)?.bucket;
+    assert_eq!(surplus.funds.native, coins(500, VALID_NATIVE));
+    assert_eq!(surplus.paid_for, None);
+    ensure!(get_bucket(router, &sam, 2).is_err(), here("Sam bucket 2 moved", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Settled, surplus stays in the same Bucket
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let john_before = router.wrap().query_balance(john.address.to_string(), VALID_NATIVE)?;
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), fuzionmarket.clone(), &buy(2, 3, true), &[]);
+    ensure!(res.is_ok(), here("Sam buy listing 2 settled", line!(), column!()));
+
+    let john_after = router.wrap().query_balance(john.address.to_string(), VALID_NATIVE)?;
+    assert_eq!(john_after.amount - john_before.amount, Uint128::from(995u32));
+    let leftover = get_bucket(router, &sam, 3)?.bucket;
+    assert_eq!(leftover.funds.native, coins(1_000, VALID_NATIVE));
+
+    Ok(())
+}
+
+// Ask Addresses
+// <X> Malformed & wrong kinds of address are rejected
+// <X> Real cw20 & cw721 contracts are accepted
+// <X> ChangeAsk is checked the same way
+#[test]
+fn ask_addresses() -> Result<(), anyhow::Error> {
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+
+    let cw20_ask = |address: Addr| GenericBalance {
+        native: vec![],
+        cw20: vec![Cw20CoinVerified {
+            address,
+            amount: Uint128::from(10u32),
+        }],
+        nfts: vec![],
+    };
+    let nft_ask = |contract_address: Addr| GenericBalance {
+        native: vec![],
+        cw20: vec![],
+        nfts: vec![Nft {
+            contract_address,
+            token_id: "1".to_string(),
+        }],
+    };
+    let create = |ask: GenericBalance| ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            asks: vec![ask],
+            buyer_restriction: None,
+        },
+    };
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Malformed & wrong kinds of address
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let invalid = [
+        (cw20_ask(Addr::unchecked(jvone.addr().as_str().to_uppercase())), "invalid address"),
+        (cw20_ask(Addr::unchecked("")), "invalid address"),
+        (cw20_ask(sam.address.clone()), "not a valid token contract"),
+        (cw20_ask(neonpeepz.addr()), "not a valid token contract"),
+        (nft_ask(Addr::unchecked(neonpeepz.addr().as_str().to_uppercase())), "invalid address"),
+        (nft_ask(sam.address.clone()), "not a valid token contract"),
+        (nft_ask(jvone.addr()), "not a valid token contract"),
+    ];
+    for (ask, expected) in invalid {
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &create(ask),
+            &coins(1_000, VALID_NATIVE),
+        );
+        let err = res.expect_err(&here("John create invalid ask", line!(), column!()));
+        ensure!(
+            err.root_cause().to_string().contains(expected),
+            here(format!("Expected {expected}"), line!(), column!())
+        );
+    }
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Real contracts
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &create(cw20_ask(jvone.addr())),
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John create cw20 ask", line!(), column!()));
+
+    // ChangeAsk is checked the same way
+    let change_ask = |ask: GenericBalance| ExecuteMsg::ChangeAsk {
+        listing_id: 1,
+        new_asks: vec![ask],
+    };
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &change_ask(nft_ask(sam.address.clone())),
+        &[],
+    );
+    ensure!(res.is_err(), here("John change to invalid ask", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &change_ask(nft_ask(neonpeepz.addr())),
+        &[],
+    );
+    ensure!(res.is_ok(), here("John change to nft ask", line!(), column!()));
+
+    Ok(())
+}
+
+// Token Lists
+// <X> Only Admin can set token lists
+// <X> Deny & Allow lists apply to receive hooks, asks & native funds
+// <X> Assets already held can still be withdrawn
+// <X> Open lists allow everything
+#[test]
+fn token_lists() -> Result<(), anyhow::Error> {
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, jvtwo, _jvtre, neonpeepz, shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+
+    let set_lists = |mode: ListMode, natives: Vec<String>, cw20s: Vec<Addr>, cw721s: Vec<Addr>| {
+        ExecuteMsg::SetTokenLists {
+            token_lists: TokenLists {
+                mode,
+                natives,
+                cw20s,
+                cw721s,
+            },
+        }
+    };
+    let cw20_bucket = |amount: u32| cw20_base::msg::ExecuteMsg::Send {
+        contract: fuzionmarket.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&ReceiveMsg::CreateBucketCw20 {}).unwrap(),
+    };
+    let nft_bucket = |token_id: &str| -> cw721_base::ExecuteMsg<Option<Empty>, Empty> {
+        cw721_base::msg::ExecuteMsg::SendNft {
+            contract: fuzionmarket.to_string(),
+            token_id: token_id.to_string(),
+            msg: to_binary(&ReceiveNftMsg::CreateBucketCw721 {}).unwrap(),
+        }
+    };
+    let create_listing = |ask: GenericBalance| ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            asks: vec![ask],
+            buyer_restriction: None,
+        },
+    };
+    let juno_ask = GenericBalance {
+        native: coins(1_000, VALID_NATIVE),
+        cw20: vec![],
+        nfts: vec![],
+    };
+
+    // Only admin
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &set_lists(ListMode::Deny, vec![], vec![], vec![]),
+        &[],
+    );
+    ensure!(res.is_err(), here("John set token lists", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Deny JVTWO & Shitty Kittyz
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &set_lists(ListMode::Deny, vec![], vec![jvtwo.addr()], vec![shittykittyz.addr()]),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin set deny lists", line!(), column!()));
+
+    // Receive hooks
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), jvtwo.addr(), &cw20_bucket(10), &[]);
+    let err = res.expect_err(&here("John bucket denied cw20", line!(), column!()));
+    ensure!(
+        err.root_cause().to_string().contains("can't be traded"),
+        here("Expected TokenNotAllowed", line!(), column!())
+    );
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), shittykittyz.addr(), &nft_bucket("1"), &[]);
+    ensure!(res.is_err(), here("John bucket denied nft", line!(), column!()));
+
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), jvone.addr(), &cw20_bucket(10), &[]);
+    ensure!(res.is_ok(), here("John bucket JVONE", line!(), column!()));
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), neonpeepz.addr(), &nft_bucket("1"), &[]);
+    ensure!(res.is_ok(), here("John bucket Neon Peepz", line!(), column!()));
+
+    // Asks
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &create_listing(GenericBalance {
+            native: vec![],
+            cw20: vec![Cw20CoinVerified {
+                address: jvtwo.addr(),
+                amount: Uint128::from(10u32),
+            }],
+            nfts: vec![],
+        }),
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_err(), here("John ask for denied cw20", line!(), column!()));
+
+    // Listing 1 | 1_000 JUNO for 1_000 JUNO
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &create_listing(juno_ask.clone()),
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Allow only JVONE & Neon Peepz, no natives
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &set_lists(ListMode::Allow, vec![], vec![jvone.addr()], vec![neonpeepz.addr()]),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin set allow lists", line!(), column!()));
+
+    // Native funds
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateBucket {},
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_err(), here("John bucket native", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::AddToListing {
+            listing_id: 1,
+        },
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_err(), here("John add native to listing", line!(), column!()));
+
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), jvtwo.addr(), &cw20_bucket(10), &[]);
+    ensure!(res.is_err(), here("John bucket unlisted cw20", line!(), column!()));
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), jvone.addr(), &cw20_bucket(10), &[]);
+    ensure!(res.is_ok(), here("John bucket allowed cw20", line!(), column!()));
+
+    // Assets already held can still be withdrawn
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::DeleteListing {
+            listing_id: 1,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John delete native listing", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Open
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        contract_admin.address.clone(),
+        fuzionmarket.clone(),
+        &set_lists(ListMode::Open, vec![], vec![jvone.addr()], vec![]),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin set open", line!(), column!()));
+
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), jvtwo.addr(), &cw20_bucket(10), &[]);
+    ensure!(res.is_ok(), here("John bucket JVTWO", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateBucket {},
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John bucket native", line!(), column!()));
+
+    Ok(())
+}
+
+// Stable Keys
+// <X> Ids, creator & owner are kept when bought, claimants are set
+// <X> Owner queries return what each address holds
+// <X> Only the claimants can act on a purchase
+#[test]
+fn stable_keys() -> Result<(), anyhow::Error> {
+    use crate::query::{BucketResponse, GetBucketsResponse, ListingResponse, MultiListingResponse};
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Listings
+    // 1 | john | bought by sam
+    // 2 | john | not bought
+    // FOR_SALE: 10_000 JUNO
+    // PRICE: 1_000 JUNO
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    for listing_id in [1, 2] {
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &ExecuteMsg::CreateListing {
+                create_msg: CreateListingMsg {
+                    asks: vec![GenericBalance {
+                        native: coins(1_000, VALID_NATIVE),
+                        cw20: vec![],
+                        nfts: vec![],
+                    }],
+                    buyer_restriction: None,
+                },
+            },
+            &coins(10_000, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &ExecuteMsg::Finalize {
+                listing_id,
+                expiration: expires_in(router, 1000),
+                start: None,
+            },
+            &[],
+        );
+        ensure!(res.is_ok(), here("John finalize", line!(), column!()));
+    }
+
+    // Bucket 1 | 1_000 JUNO
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateBucket {},
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("Sam create bucket", line!(), column!()));
+
+    let buy = |listing_id: u64| ExecuteMsg::BuyListing {
+        listing_id,
+        bucket_id: 1,
+        ask_index: 0,
+        settle: false,
+    };
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), fuzionmarket.clone(), &buy(1), &[]);
+    ensure!(res.is_ok(), here("Sam buy listing 1", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Ids & creator / owner are kept, claimants are set
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let listing = router
+        .wrap()
+        .query_wasm_smart::<ListingResponse>(
+            fuzionmarket.clone(),
+            &QueryMsg::GetListing {
+                listing_id: 1,
+            },
+        )?
+        .listing;
+    assert_eq!(listing.creator, john.address);
+    assert_eq!(listing.claimant, Some(sam.address.clone()));
+
+    let get_bucket = |router: &App, owner: &User| {
+        router.wrap().query_wasm_smart::<BucketResponse>(
+            fuzionmarket.clone(),
+            &QueryMsg::GetBucket {
+                owner: owner.address.to_string(),
+                bucket_id: 1,
+            },
+        )
+    };
+    let bucket = get_bucket(router, &john)?.bucket;
+    assert_eq!(bucket.owner, sam.address);
+    assert_eq!(bucket.claimant, Some(john.address.clone()));
+    ensure!(
+        get_bucket(router, &sam).is_err(),
+        here("Sam no longer holds bucket 1", line!(), column!())
+    );
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Owner queries return what each address holds
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let listings_of = |router: &App, owner: &User| {
+        router.wrap().query_wasm_smart::<MultiListingResponse>(
+            fuzionmarket.clone(),
+            &QueryMsg::GetListingsByOwner {
+                owner: owner.address.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+    };
+    let ids = |res: MultiListingResponse| res.listings.iter().map(|l| l.id).collect::<Vec<_>>();
+    assert_eq!(ids(listings_of(router, &john)?), vec![2]);
+    assert_eq!(ids(listings_of(router, &sam)?), vec![1]);
+
+    let buckets_of = |router: &App, owner: &User| {
+        router.wrap().query_wasm_smart::<GetBucketsResponse>(
+            fuzionmarket.clone(),
+            &QueryMsg::GetBuckets {
+                bucket_owner: owner.address.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+    };
+    assert_eq!(buckets_of(router, &john)?.buckets.len(), 1);
+    assert_eq!(buckets_of(router, &sam)?.buckets.len(), 0);
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Only the claimants can act on a purchase
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), fuzionmarket.clone(), &buy(2), &[]);
+    ensure!(res.is_err(), here("Sam buy with paid bucket", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::RemoveBucket {
+            bucket_id: 1,
+        },
+        &[],
+    );
+    ensure!(res.is_err(), here("Sam remove paid bucket", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::DeleteListing {
+            listing_id: 1,
+        },
+        &[],
+    );
+    ensure!(res.is_err(), here("John delete sold listing", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::WithdrawPurchased {
+            listing_id: 1,
+        },
+        &[],
+    );
+    ensure!(res.is_err(), here("John withdraw purchased", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::RemoveBucket {
+            bucket_id: 1,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John remove bucket", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::WithdrawPurchased {
+            listing_id: 1,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Sam withdraw purchased", line!(), column!()));
+
+    assert_eq!(buckets_of(router, &john)?.buckets.len(), 0);
+    assert_eq!(ids(listings_of(router, &sam)?), Vec::<u64>::new());
+
+    Ok(())
+}
+
+// Trade History
+// <X> Trades are recorded for Bucket & funds purchases
+// <X> Withdrawing doesn't remove a Trade
+// <X> Queries by seller, buyer, collection & time range, paginated
+#[test]
+fn trade_history() -> Result<(), anyhow::Error> {
+    use crate::query::TradesResponse;
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, neonpeepz, shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let juno = |amount: u128| GenericBalance {
+        native: coins(amount, VALID_NATIVE),
+        cw20: vec![],
+        nfts: vec![],
+    };
+    let start = router.block_info().time;
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Trade 1
+    // John sells neonpeepz 1 for 1_000 JUNO
+    // Sam pays with Bucket 1
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let createmsg_nft: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
+        cw721_base::msg::ExecuteMsg::SendNft {
+            contract: fuzionmarket.to_string(),
+            token_id: "1".to_string(),
+            msg: to_binary(&crate::msg::ReceiveNftMsg::CreateListingCw721 {
+                create_msg: CreateListingMsg {
+                    asks: vec![juno(1_000)],
+                    buyer_restriction: None,
+                },
+            })?,
+        };
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), neonpeepz.addr(), &createmsg_nft, &[]);
+    ensure!(res.is_ok(), here("John create NFT listing", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::Finalize {
+            listing_id: 1,
+            expiration: expires_in(router, 1000),
+            start: None,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John finalize", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateBucket {},
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("Sam create bucket", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::BuyListing {
+            listing_id: 1,
+            bucket_id: 1,
+            ask_index: 0,
+            settle: false,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Sam buy listing 1", line!(), column!()));
+
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 10;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(100);
+    });
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Trade 2
+    // Sam sells 10_000 JUNO for 1_000 JUNO
+    // John pays with funds
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateListing {
+            create_msg: CreateListingMsg {
+                asks: vec![juno(1_000)],
+                buyer_restriction: None,
+            },
+        },
+        &coins(10_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("Sam create listing", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::Finalize {
+            listing_id: 2,
+            expiration: expires_in(router, 1000),
+            start: None,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Sam finalize", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::BuyListingWithFunds {
+            listing_id: 2,
+            ask_index: 0,
+        },
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John buy listing 2 with funds", line!(), column!()));
+
+    // Withdrawing trade 1 doesn't remove it from the history
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::WithdrawPurchased {
+            listing_id: 1,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Sam withdraw purchased", line!(), column!()));
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::RemoveBucket {
+            bucket_id: 1,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John remove bucket", line!(), column!()));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Queries
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let query = |router: &App, msg: &QueryMsg| {
+        router.wrap().query_wasm_smart::<TradesResponse>(fuzionmarket.clone(), msg)
+    };
+    let ids = |res: TradesResponse| res.trades.iter().map(|t| t.id).collect::<Vec<_>>();
+    let by_seller = |seller: &User| QueryMsg::GetTradesBySeller {
+        seller: seller.address.to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let by_buyer = |buyer: &User| QueryMsg::GetTradesByBuyer {
+        buyer: buyer.address.to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let by_collection = |collection: Addr| QueryMsg::GetTradesByCollection {
+        collection: collection.to_string(),
+        start_after: None,
+        limit: None,
+    };
+
+    let res = query(router, &by_seller(&john))?;
+    assert_eq!(ids(res.clone()), vec![1]);
+    let trade = &res.trades[0];
+    assert_eq!(trade.listing_id, 1);
+    assert_eq!(trade.bucket_id, Some(1));
+    assert_eq!(trade.seller, john.address);
+    assert_eq!(trade.buyer, sam.address);
+    assert_eq!(trade.for_sale.nfts.len(), 1);
+    assert_eq!(trade.paid, juno(1_000));
+    assert_eq!(trade.listing_fee, vec![]);
+    assert_eq!(trade.ask_fee, coins(5, VALID_NATIVE));
+    assert_eq!(trade.time, start);
+
+    let res = query(router, &by_seller(&sam))?;
+    assert_eq!(ids(res.clone()), vec![2]);
+    let trade = &res.trades[0];
+    assert_eq!(trade.bucket_id, None);
+    assert_eq!(trade.for_sale, juno(10_000));
+    assert_eq!(trade.listing_fee, coins(50, VALID_NATIVE));
+    assert_eq!(trade.ask_fee, coins(5, VALID_NATIVE));
+    assert_eq!(trade.time, start.plus_seconds(100));
+
+    assert_eq!(ids(query(router, &by_buyer(&sam))?), vec![1]);
+    assert_eq!(ids(query(router, &by_buyer(&john))?), vec![2]);
+    assert_eq!(ids(query(router, &by_buyer(&max))?), Vec::<u64>::new());
+
+    assert_eq!(ids(query(router, &by_collection(neonpeepz.addr()))?), vec![1]);
+    assert_eq!(ids(query(router, &by_collection(shittykittyz.addr()))?), Vec::<u64>::new());
+
+    // Time range
+    let by_time =
+        |from: Option<u64>, to: Option<u64>, start_after: Option<u64>| QueryMsg::GetTradesByTime {
+            from: from.map(|s| start.plus_seconds(s)),
+            to: to.map(|s| start.plus_seconds(s)),
+            start_after,
+            limit: Some(1),
+        };
+    assert_eq!(ids(query(router, &by_time(Some(50), None, None))?), vec![2]);
+    assert_eq!(ids(query(router, &by_time(None, Some(50), None))?), vec![1]);
+    assert_eq!(ids(query(router, &by_time(Some(101), None, None))?), Vec::<u64>::new());
+
+    // Paginated
+    let res = query(router, &by_time(None, None, None))?;
+    assert_eq!(ids(res.clone()), vec![1]);
+    assert_eq!(res.next, Some(1));
+    let res = query(router, &by_time(None, None, res.next))?;
+    assert_eq!(ids(res.clone()), vec![2]);
+    assert_eq!(res.next, None);
+
+    Ok(())
+}
+
+// Market Stats
+// <X> Listing counts follow every status change
+// <X> Trade count, volumes & fees after purchases
+// <X> Totals are paginated
+#[test]
+fn market_stats() -> Result<(), anyhow::Error> {
+    use crate::query::{StatTotal, StatTotalsResponse, StatsResponse};
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, fuzionmarket) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give native balances to all users
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let juno_ask = CreateListingMsg {
+        asks: vec![GenericBalance {
+            native: coins(1_000, VALID_NATIVE),
+            cw20: vec![],
+            nfts: vec![],
+        }],
+        buyer_restriction: None,
+    };
+    let get_stats = |router: &App| {
+        router
+            .wrap()
+            .query_wasm_smart::<StatsResponse>(fuzionmarket.clone(), &QueryMsg::GetStats {})
+            .map(|res| res.stats)
+    };
+    let get_totals = |router: &App, total: StatTotal| {
+        router.wrap().query_wasm_smart::<StatTotalsResponse>(
+            fuzionmarket.clone(),
+            &QueryMsg::GetStatTotals {
+                total,
+                start_after: None,
+                limit: None,
+            },
+        )
+    };
+    let counts = |being_prepared: u64, finalized_ready: u64, closed: u64| ListingCounts {
+        being_prepared,
+        finalized_ready,
+        closed,
+    };
+
+    assert_eq!(get_stats(router)?, Stats::default());
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Listings
+    // 1 | 10_000 JUNO
+    // 2 | 500 JUNO, deleted
+    // 3 | neonpeepz 1
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    for amount in [10_000, 500] {
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &ExecuteMsg::CreateListing {
+                create_msg: juno_ask.clone(),
+            },
+            &coins(amount, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+    }
+    let createmsg_nft: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
+        cw721_base::msg::ExecuteMsg::SendNft {
+            contract: fuzionmarket.to_string(),
+            token_id: "1".to_string(),
+            msg: to_binary(&crate::msg::ReceiveNftMsg::CreateListingCw721 {
+                create_msg: juno_ask.clone(),
+            })?,
+        };
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), neonpeepz.addr(), &createmsg_nft, &[]);
+    ensure!(res.is_ok(), here("John create NFT listing", line!(), column!()));
+    assert_eq!(get_stats(router)?.listings, counts(3, 0, 0));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::DeleteListing {
+            listing_id: 2,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John delete listing", line!(), column!()));
+    assert_eq!(get_stats(router)?.listings, counts(2, 0, 0));
+
+    for listing_id in [1, 3] {
+        let res: Result<AppResponse> = router.execute_contract(
+            john.address.clone(),
+            fuzionmarket.clone(),
+            &ExecuteMsg::Finalize {
+                listing_id,
+                expiration: expires_in(router, 1000),
+                start: None,
+            },
+            &[],
+        );
+        ensure!(res.is_ok(), here("John finalize", line!(), column!()));
+    }
+    assert_eq!(get_stats(router)?.listings, counts(0, 2, 0));
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Sam buys 1 with a Bucket & 3 with funds
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::CreateBucket {},
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("Sam create bucket", line!(), column!()));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::BuyListing {
+            listing_id: 1,
+            bucket_id: 1,
+            ask_index: 0,
+            settle: false,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Sam buy listing 1", line!(), column!()));
+    assert_eq!(get_stats(router)?.listings, counts(0, 1, 1));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::BuyListingWithFunds {
+            listing_id: 3,
+            ask_index: 0,
+        },
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("Sam buy listing 3 with funds", line!(), column!()));
+    assert_eq!(get_stats(router)?.listings, counts(0, 0, 1));
+
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        fuzionmarket.clone(),
+        &ExecuteMsg::WithdrawPurchased {
+            listing_id: 1,
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("Sam withdraw purchased", line!(), column!()));
+
+    // Volume counts both sides, fees are 0.5% of each JUNO side
+    assert_eq!(
+        get_stats(router)?,
+        Stats {
+            listings: counts(0, 0, 0),
+            trades: 2,
+        }
+    );
+    assert_eq!(
+        get_totals(router, StatTotal::NativeVolume)?,
+        StatTotalsResponse {
+            totals: vec![(VALID_NATIVE.to_string(), Uint128::new(12_000))],
+            next: None,
+        }
+    );
+    assert_eq!(get_totals(router, StatTotal::Cw20Volume)?.totals, vec![]);
+    assert_eq!(
+        get_totals(router, StatTotal::NftsTraded)?.totals,
+        vec![(neonpeepz.addr().to_string(), Uint128::one())]
+    );
+    assert_eq!(
+        get_totals(router, StatTotal::Fees)?.totals,
+        vec![(VALID_NATIVE.to_string(), Uint128::new(60))]
+    );
+
+    // Paging past the only denom
+    let res: StatTotalsResponse = router.wrap().query_wasm_smart(
+        fuzionmarket.clone(),
+        &QueryMsg::GetStatTotals {
+            total: StatTotal::NativeVolume,
+            start_after: Some(VALID_NATIVE.to_string()),
+            limit: None,
+        },
+    )?;
+    assert!(res.totals.is_empty());
+
+    Ok(())
+}
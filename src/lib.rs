@@ -3,6 +3,7 @@ pub mod error;
 pub use crate::error::ContractError;
 pub mod execute;
 pub mod integration_tests;
+pub mod migrate;
 pub mod msg;
 pub mod query;
 pub mod state;
@@ -13,40 +14,57 @@ mod contract_imports {
         entry_point, from_binary, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response,
         StdResult,
     };
-    pub use cw2::set_contract_version;
+    pub use cw2::{get_contract_version, set_contract_version};
     pub use cw20::{Balance, Cw20CoinVerified, Cw20ReceiveMsg};
     pub use cw721::Cw721ReceiveMsg;
+    pub use semver::Version;
 
     pub use crate::error::ContractError;
     pub use crate::execute::{
-        execute_add_to_bucket, execute_add_to_bucket_cw721, execute_add_to_listing,
-        execute_add_to_listing_cw721, execute_buy_listing, execute_change_ask,
+        execute_accept_admin, execute_add_to_bucket, execute_add_to_bucket_cw721,
+        execute_add_to_listing, execute_add_to_listing_cw721, execute_buy_listing,
+        execute_buy_listing_with_funds, execute_cancel_listing, execute_change_ask,
         execute_create_bucket, execute_create_bucket_cw721, execute_create_listing,
-        execute_create_listing_cw721, execute_delete_listing, execute_finalize,
-        execute_withdraw_bucket, execute_withdraw_purchased,
+        execute_create_listing_cw721, execute_delete_listing, execute_extend_expiration,
+        execute_finalize, execute_propose_admin, execute_relist, execute_remove_from_listing,
+        execute_set_pause, execute_set_token_lists, execute_update_config,
+        execute_update_fee_config, execute_withdraw_bucket, execute_withdraw_from_bucket,
+        execute_withdraw_purchased,
+    };
+    pub use crate::migrate::run_migrations;
+    pub use crate::msg::{
+        ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, ReceiveMsg, ReceiveNftMsg,
     };
-    pub use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg, ReceiveNftMsg};
     pub use crate::query::*;
     //get_all_listings, get_buckets, get_listings_by_owner, get_listings_for_market, get_counts
-    pub use crate::state::{FeeDenom, Nft, BUCKET_COUNT, FEE_DENOM, LISTING_COUNT};
+    pub use crate::state::{
+        Config, FeeRecipient, Nft, Pause, Stats, BUCKET_COUNT, CONFIG, FEE_CONFIG, LISTING_COUNT,
+        STATS, TRADE_COUNT,
+    };
 }
 
 mod execute_imports {
     pub use crate::error::ContractError;
     pub use crate::msg::CreateListingMsg;
     pub use crate::state::{
-        genbal_cmp, listingz, BalanceUtil, Bucket, FeeDenom, GenericBalance, Listing, Nft, Status,
-        BUCKETS, BUCKET_COUNT, FEE_DENOM, LISTING_COUNT,
+        add_fee_totals, add_trade_totals, bucketz, genbal_cmp, genbal_contains, listingz, trades,
+        BalanceUtil, Bucket, Config, FeeConfig, FeeRecipient, GenericBalance, Limits, Listing, Nft,
+        Pause, PauseAction, Stats, Status, TokenLists, Trade, BUCKET_COUNT, CONFIG, FEE_CONFIG,
+        LISTING_COUNT, STATS, TRADE_COLLECTIONS, TRADE_COUNT,
     };
     pub use crate::utils::{
-        calc_fee_coin,
+        calc_fee_coins,
         send_tokens_cosmos, //calc_fee, check_whitelist, check_valid_genbal
                             //check_buyer_whitelisted, get_whitelisted_addresses, get_whitelisted_buyers, normalize_ask,
     };
-    pub use cosmwasm_std::{Addr, DepsMut, Env, Response, StdError};
+    pub use cosmwasm_std::{
+        Addr, CosmosMsg, Deps, DepsMut, Empty, Env, Event, Response, StdError, Storage,
+    };
     pub use cw20::Balance;
+    pub use cw_utils::{Expiration, Scheduled};
 }
 
+#[cfg(test)]
 mod integration_tests_imports {
     pub use anyhow::ensure;
     pub use core::fmt::Display;
@@ -54,6 +72,21 @@ mod integration_tests_imports {
     pub use crate::{msg::*, state::*};
     pub use cosmwasm_std::{coins, to_binary, Addr, Coin, Empty, Uint128}; //BlockInfo;
     pub use cw20::{Cw20Coin, Cw20CoinVerified, Cw20Contract};
+    pub use cw_utils::{Expiration, Scheduled};
+}
+
+mod migrate_imports {
+    pub use crate::error::ContractError;
+    pub use crate::msg::MigrateMsg;
+    pub use crate::state::{
+        BuyerRestriction, Config, FeeConfig, FeeRecipient, GenericBalance, Pause, Stats, Status,
+        TokenLists, CONFIG, FEE_CONFIG, STATS, TRADE_COUNT,
+    };
+    pub use cosmwasm_schema::cw_serde;
+    pub use cosmwasm_std::{Addr, Coin, DepsMut, Order, StdResult, Timestamp};
+    pub use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, UniqueIndex};
+    pub use cw_utils::Expiration;
+    pub use semver::Version;
 }
 
 mod msg_imports {
@@ -62,37 +95,49 @@ mod msg_imports {
     pub use cw20::Cw20ReceiveMsg;
     pub use cw721::Cw721ReceiveMsg;
     //GetBucketsResponse, MultiListingResponse, CountResponse
-    pub use crate::state::GenericBalance;
+    pub use crate::state::{
+        BuyerRestriction, FeeConfig, FeeRecipient, GenericBalance, Limits, Pause, PauseAction,
+        TokenLists,
+    };
+    pub use cosmwasm_std::Timestamp;
+    pub use cw_utils::{Expiration, Scheduled};
 }
 
 mod query_imports {
     pub use crate::state::{
-        listingz, Bucket, FeeDenom, Listing, Status, BUCKETS, BUCKET_COUNT, FEE_DENOM,
-        LISTING_COUNT,
+        bucketz, expiration_key, listingz, trades, Bucket, Config, FeeDenom, GenericBalance,
+        Listing, Stats, Status, Trade, BUCKET_COUNT, CONFIG, CW20_VOLUME, FEE_CONFIG, FEE_TOTALS,
+        LISTING_COUNT, NATIVE_VOLUME, NFTS_TRADED, STATS, TRADE_COLLECTIONS,
     };
     pub use cosmwasm_schema::cw_serde;
-    pub use cosmwasm_std::{Addr, Deps, Env, Order, StdError, StdResult};
-    pub use cw_storage_plus::PrefixBound;
+    pub use cosmwasm_std::{
+        Addr, BlockInfo, Deps, Env, Order, StdError, StdResult, Timestamp, Uint128,
+    };
+    pub use cw_storage_plus::{Bound, Map};
 }
 
 mod state_imports {
     pub use crate::error::ContractError;
     pub use crate::utils::send_tokens_cosmos;
     pub use cosmwasm_schema::cw_serde;
-    pub use cosmwasm_std::{Addr, Coin, CosmosMsg, Timestamp, Uint128};
-    pub use cw20::{Balance, Cw20CoinVerified};
-    pub use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, UniqueIndex};
-    pub use std::collections::BTreeMap;
+    pub use cosmwasm_std::{
+        Addr, Api, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, Deps, Empty, QuerierWrapper,
+        StdResult, Storage, Timestamp, Uint128,
+    };
+    pub use cw20::{Balance, Cw20CoinVerified, Cw20QueryMsg, TokenInfoResponse};
+    pub use cw721::{ContractInfoResponse, Cw721QueryMsg, TokensResponse};
+    pub use cw_storage_plus::{
+        Index, IndexList, IndexedMap, Item, Map, MultiIndex, Prefix, PrimaryKey,
+    };
+    pub use cw_utils::{Expiration, Scheduled};
+    pub use std::collections::{BTreeMap, BTreeSet};
 }
 
 mod utils_imports {
-    pub use crate::error::ContractError;
-    pub use crate::state::{FeeDenom, GenericBalance, Listing};
+    pub use crate::state::{FeeConfig, GenericBalance, MAX_FEE_BPS};
     pub use cosmwasm_std::{
-        coin, coins, to_binary, Addr, BankMsg, Coin, CosmosMsg, DepsMut, Empty, StdError,
-        StdResult, WasmMsg,
+        coin, to_binary, Addr, BankMsg, Coin, CosmosMsg, Empty, StdResult, WasmMsg,
     };
     pub use cw20::Cw20ExecuteMsg;
     pub use cw721::Cw721ExecuteMsg;
-    pub use std::collections::BTreeMap;
 }
@@ -0,0 +1,268 @@
+use crate::migrate_imports::*;
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Migrations
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+pub type Migration = fn(DepsMut, &MigrateMsg) -> Result<(), ContractError>;
+
+/// Every storage migration, oldest first
+///
+/// Each entry upgrades storage written by `version` to the layout of the next entry
+/// (or the current layout if it's the last one)
+pub const MIGRATIONS: &[(&str, Migration)] = &[("0.2.0", v0_2_0::migrate)];
+
+/// First version that writes the current storage layout, bump it when adding a migration
+pub const CURRENT_LAYOUT: &str = "0.3.0";
+
+/// Runs every migration needed to bring storage written by `from` up to date
+///
+/// `from` must be a version in `MIGRATIONS` or at least `CURRENT_LAYOUT`,
+/// any other version wrote a layout we don't know how to read
+///
+/// Returns the versions that were migrated from, in the order they were run
+pub fn run_migrations(
+    mut deps: DepsMut,
+    from: &Version,
+    msg: &MigrateMsg,
+) -> Result<Vec<String>, ContractError> {
+    let parse = |version: &str| {
+        Version::parse(version)
+            .map_err(|e| ContractError::GenericError(format!("Invalid migration version: {e}")))
+    };
+
+    if from >= &parse(CURRENT_LAYOUT)? {
+        return Ok(vec![]);
+    }
+
+    let mut applied = vec![];
+
+    for (version, migration) in MIGRATIONS {
+        let step = parse(version)?;
+
+        // Start at the step matching `from`, then run every later one
+        if applied.is_empty() && from != &step {
+            continue;
+        }
+
+        migration(deps.branch(), msg)?;
+        applied.push(step.to_string());
+    }
+
+    if applied.is_empty() {
+        return Err(ContractError::MigrationUnsupported(from.to_string()));
+    }
+
+    Ok(applied)
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// v0.2.0
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Storage layout as written by v0.2.0
+///
+/// - No `Config` or admin
+/// - Single `FeeDenom` stored at "fee_denom"
+/// - `Listing.fee_amount` & `Bucket.fee_amount` are `Option<Coin>`
+/// - `Listing.expiration_time` is an `Option<Timestamp>`
+/// - `Listing.whitelisted_buyer` is a single `Option<Addr>`
+pub mod v0_2_0 {
+    use super::*;
+
+    pub const FEE_DENOM: Item<FeeDenom> = Item::new("fee_denom");
+
+    #[cw_serde]
+    pub enum FeeDenom {
+        JUNO,
+        USDC,
+    }
+
+    impl FeeDenom {
+        pub fn value(&self) -> String {
+            match *self {
+                FeeDenom::JUNO => "ujunox".to_string(),
+                FeeDenom::USDC => "uusdcx".to_string(),
+            }
+        }
+    }
+
+    pub struct ListingIndexes<'a> {
+        pub id: UniqueIndex<'a, u64, Listing, (&'a Addr, u64)>,
+        pub finalized_date: MultiIndex<'a, u64, Listing, (&'a Addr, u64)>,
+        pub whitelisted_buyer: UniqueIndex<'a, (String, u64), Listing, (&'a Addr, u64)>,
+    }
+
+    impl IndexList<Listing> for ListingIndexes<'_> {
+        fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Listing>> + '_> {
+            let v: Vec<&dyn Index<Listing>> =
+                vec![&self.id, &self.finalized_date, &self.whitelisted_buyer];
+            Box::new(v.into_iter())
+        }
+    }
+
+    /// Listings were keyed by their current holder, `state::listingz()` moved to new namespaces
+    ///
+    /// Unique indexes hold a copy of the Listing, so they're removed along with the primary entry
+    #[must_use]
+    pub fn listingz<'a>() -> IndexedMap<'a, (&'a Addr, u64), Listing, ListingIndexes<'a>> {
+        let indexes = ListingIndexes {
+            id: UniqueIndex::new(|a_listing| a_listing.id, "listing__id"),
+            finalized_date: MultiIndex::new(
+                |_pk, a_listing| a_listing.finalized_time.map_or(0_u64, |x| x.seconds()),
+                "listings_im",
+                "listing__finalized__date",
+            ),
+            whitelisted_buyer: UniqueIndex::new(
+                |listing| {
+                    (
+                        listing
+                            .whitelisted_buyer
+                            .clone()
+                            .map_or_else(|| "1".to_string(), |addr| addr.to_string()),
+                        listing.id,
+                    )
+                },
+                "listing__whitelisted__buyer",
+            ),
+        };
+
+        IndexedMap::new("listings_im", indexes)
+    }
+
+    /// Buckets were keyed by their current holder, `state::bucketz()` moved to a new namespace
+    pub const BUCKETS: Map<(Addr, u64), Bucket> = Map::new("buckets");
+
+    #[cw_serde]
+    pub struct Listing {
+        pub creator: Addr,
+        pub id: u64,
+        pub finalized_time: Option<Timestamp>,
+        pub expiration_time: Option<Timestamp>,
+        pub status: Status,
+        pub claimant: Option<Addr>,
+        pub whitelisted_buyer: Option<Addr>,
+        pub for_sale: GenericBalance,
+        pub ask: GenericBalance,
+        pub fee_amount: Option<Coin>,
+    }
+
+    #[cw_serde]
+    pub struct Bucket {
+        pub owner: Addr,
+        pub funds: GenericBalance,
+        pub fee_amount: Option<Coin>,
+    }
+
+    /// v0.2.0 -> current
+    ///
+    /// - Saves `Config`, `MigrateMsg.admin` is required as v0.2.0 had no admin
+    /// - Replaces `FEE_DENOM` with a `FeeConfig` charging the old 0.5% on that denom
+    /// - Rewrites every Listing (and its index entries) & Bucket with `fee_amount` as a `Vec<Coin>`
+    ///   `expiration_time` as an `Expiration::AtTime` & `whitelisted_buyer` as a `BuyerRestriction::Address`
+    /// - Starts `TRADE_COUNT` & `STATS`, trades before the migration aren't recorded
+    /// - Moves Listings & Buckets to be keyed by id alone, v0.2.0 didn't keep the seller of a
+    ///   purchased Listing so its `creator` stays the buyer
+    /// - Buckets holding sale proceeds keep their `fee_amount`, which keeps them committed
+    ///   (see `Bucket::is_committed`) even though the Listing they paid for wasn't recorded
+    pub fn migrate(deps: DepsMut, msg: &MigrateMsg) -> Result<(), ContractError> {
+        let Some(admin) = &msg.admin else {
+            return Err(ContractError::MissingInit("admin".to_string()));
+        };
+
+        let admin = deps.api.addr_validate(admin).map_err(|_| ContractError::InitInvalidAddr)?;
+
+        let fee_recipient = msg
+            .fee_recipient
+            .clone()
+            .unwrap_or(FeeRecipient::CommunityPool)
+            .validate(deps.api)
+            .map_err(|_| ContractError::InitInvalidAddr)?;
+
+        let limits = msg.limits.clone().unwrap_or_default();
+        limits.check_valid()?;
+
+        CONFIG.save(
+            deps.storage,
+            &Config {
+                admin,
+                pending_admin: None,
+                fee_recipient,
+                limits,
+                pause: Pause::default(),
+                token_lists: TokenLists::default(),
+            },
+        )?;
+
+        // Fee config
+        let fee_config = match &msg.fee_config {
+            Some(fee_config) => fee_config.clone(),
+            None => FeeConfig {
+                fee_denoms: vec![crate::state::FeeDenom {
+                    denom: FEE_DENOM.load(deps.storage)?.value(),
+                    bps: 50,
+                    min_fee: None,
+                }],
+            },
+        };
+        fee_config.check_valid()?;
+        FEE_CONFIG.save(deps.storage, &fee_config)?;
+        FEE_DENOM.remove(deps.storage);
+
+        // v0.2.0 kept no trade history
+        TRADE_COUNT.save(deps.storage, &1)?;
+        let mut stats = Stats::default();
+
+        // Listings
+        let old_listings = listingz()
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        for ((creator, id), old) in old_listings {
+            listingz().remove(deps.storage, (&creator, id))?;
+            stats.listings.add(&old.status);
+            crate::state::listingz().save(
+                deps.storage,
+                id,
+                &crate::state::Listing {
+                    creator: old.creator,
+                    id: old.id,
+                    finalized_time: old.finalized_time,
+                    start: None,
+                    expiration: old.expiration_time.map(Expiration::AtTime),
+                    status: old.status,
+                    claimant: old.claimant,
+                    buyer_restriction: old.whitelisted_buyer.map(BuyerRestriction::Address),
+                    for_sale: old.for_sale,
+                    asks: vec![old.ask],
+                    fee_amount: old.fee_amount.into_iter().collect(),
+                },
+            )?;
+        }
+
+        STATS.save(deps.storage, &stats)?;
+
+        // Buckets
+        let old_buckets = BUCKETS
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        for ((owner, id), old) in old_buckets {
+            BUCKETS.remove(deps.storage, (owner, id));
+            crate::state::bucketz().save(
+                deps.storage,
+                id,
+                &crate::state::Bucket {
+                    owner: old.owner,
+                    funds: old.funds,
+                    fee_amount: old.fee_amount.into_iter().collect(),
+                    paid_for: None,
+                    claimant: None,
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+}
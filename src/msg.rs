@@ -4,7 +4,34 @@ use crate::msg_imports::*;
 // Instantiate
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 #[cw_serde]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+    /// Defaults to the instantiator
+    pub admin: Option<String>,
+    /// Defaults to the Community Pool
+    pub fee_recipient: Option<FeeRecipient>,
+    pub fee_config: FeeConfig,
+    /// Defaults to `Limits::default()`
+    pub limits: Option<Limits>,
+    /// Defaults to `ListMode::Open`
+    pub token_lists: Option<TokenLists>,
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Migrate
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+/// Only read by migrations that need them, see `migrate::MIGRATIONS`
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {
+    /// Required when migrating from v0.2.0
+    pub admin: Option<String>,
+    /// Defaults to the Community Pool
+    pub fee_recipient: Option<FeeRecipient>,
+    /// Defaults to 0.5% of the v0.2.0 `FeeDenom`
+    pub fee_config: Option<FeeConfig>,
+    /// Defaults to `Limits::default()`
+    pub limits: Option<Limits>,
+}
 
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // Execute
@@ -22,14 +49,38 @@ pub enum ExecuteMsg {
     AddToListing {
         listing_id: u64,
     },
+    /// Sends `assets` back from a Listing that is still `BeingPrepared`
+    RemoveFromListing {
+        listing_id: u64,
+        assets: GenericBalance,
+    },
+    /// Replaces every ask on a Listing that is still `BeingPrepared`
     ChangeAsk {
         listing_id: u64,
-        new_ask: GenericBalance,
+        new_asks: Vec<GenericBalance>,
     },
-    // Makes Listing available for purchase & sets expiration time
+    // Makes Listing available for purchase & sets expiration
+    // Must be within `Limits.expiration`
     Finalize {
         listing_id: u64,
-        seconds: u64,
+        expiration: Expiration,
+        /// If set, Listing can't be bought until then
+        ///
+        /// Must be the same kind (time or height) as `expiration`, unless it's `Never`
+        start: Option<Scheduled>,
+    },
+    /// Puts an expired Listing back on the market without withdrawing its assets
+    ///
+    /// Clears any `start` set by `Finalize`
+    Relist {
+        listing_id: u64,
+        expiration: Expiration,
+        new_asks: Option<Vec<GenericBalance>>,
+    },
+    /// Moves a live Listing's expiration later, must be the same kind of `Expiration` or `Never`
+    ExtendExpiration {
+        listing_id: u64,
+        expiration: Expiration,
     },
     DeleteListing {
         listing_id: u64,
@@ -43,13 +94,58 @@ pub enum ExecuteMsg {
     RemoveBucket {
         bucket_id: u64,
     },
+    /// Withdraws `assets` from a Bucket, leaving the rest in it
+    WithdrawFromBucket {
+        bucket_id: u64,
+        assets: GenericBalance,
+    },
     BuyListing {
         listing_id: u64,
         bucket_id: u64,
+        /// Index of the ask in `Listing.asks` the Bucket pays
+        #[serde(default)]
+        ask_index: u32,
+        /// Pay out both sides in this tx instead of leaving them to withdraw
+        #[serde(default)]
+        settle: bool,
+    },
+    // Pays for a Listing with the attached funds & settles both sides in this tx
+    BuyListingWithFunds {
+        listing_id: u64,
+        /// Index of the ask in `Listing.asks` the funds pay
+        #[serde(default)]
+        ask_index: u32,
     },
     WithdrawPurchased {
         listing_id: u64,
     },
+    /// Takes a `FinalizedReady` Listing off the market before it expires
+    ///
+    /// Subject to `Limits.min_cancel_seconds` & `Limits.cancel_fee`
+    CancelListing {
+        listing_id: u64,
+    },
+    // Admin
+    UpdateFeeConfig {
+        fee_config: FeeConfig,
+    },
+    UpdateConfig {
+        fee_recipient: Option<FeeRecipient>,
+        limits: Option<Limits>,
+    },
+    // Admin handover, `new_admin` must call `AcceptAdmin`
+    ProposeAdmin {
+        new_admin: String,
+    },
+    AcceptAdmin {},
+    // Replaces all pause flags
+    SetPause {
+        pause: Pause,
+    },
+    /// Replaces the token allow / deny lists
+    SetTokenLists {
+        token_lists: TokenLists,
+    },
     // RemoveListing {
     //     listing_id: u64,
     // },
@@ -59,6 +155,81 @@ pub enum ExecuteMsg {
     // },
 }
 
+impl ExecuteMsg {
+    /// Pause flag this message is blocked by, `None` for Admin messages
+    ///
+    /// `Receive` is checked against `ReceiveMsg::pause_action` once decoded
+    #[must_use]
+    pub fn pause_action(&self) -> Option<PauseAction> {
+        match self {
+            ExecuteMsg::Receive(_) => None,
+            ExecuteMsg::ReceiveNft(_)
+            | ExecuteMsg::CreateListing {
+                ..
+            }
+            | ExecuteMsg::AddToListing {
+                ..
+            }
+            | ExecuteMsg::ChangeAsk {
+                ..
+            }
+            | ExecuteMsg::Finalize {
+                ..
+            }
+            | ExecuteMsg::Relist {
+                ..
+            }
+            | ExecuteMsg::ExtendExpiration {
+                ..
+            }
+            | ExecuteMsg::CreateBucket {}
+            | ExecuteMsg::AddToBucket {
+                ..
+            } => Some(PauseAction::Create),
+            ExecuteMsg::BuyListing {
+                ..
+            }
+            | ExecuteMsg::BuyListingWithFunds {
+                ..
+            } => Some(PauseAction::Buy),
+            ExecuteMsg::DeleteListing {
+                ..
+            }
+            | ExecuteMsg::RemoveBucket {
+                ..
+            }
+            | ExecuteMsg::WithdrawFromBucket {
+                ..
+            }
+            | ExecuteMsg::WithdrawPurchased {
+                ..
+            }
+            | ExecuteMsg::RemoveFromListing {
+                ..
+            }
+            | ExecuteMsg::CancelListing {
+                ..
+            } => Some(PauseAction::Withdraw),
+            ExecuteMsg::UpdateFeeConfig {
+                ..
+            }
+            | ExecuteMsg::UpdateConfig {
+                ..
+            }
+            | ExecuteMsg::ProposeAdmin {
+                ..
+            }
+            | ExecuteMsg::AcceptAdmin {}
+            | ExecuteMsg::SetPause {
+                ..
+            }
+            | ExecuteMsg::SetTokenLists {
+                ..
+            } => None,
+        }
+    }
+}
+
 // cw20 entry point
 #[cw_serde]
 pub enum ReceiveMsg {
@@ -77,6 +248,26 @@ pub enum ReceiveMsg {
     AddToBucketCw20 {
         bucket_id: u64,
     },
+    // Pays for a Listing with the sent cw20 & settles both sides in this tx
+    BuyListingCw20 {
+        listing_id: u64,
+        /// Index of the ask in `Listing.asks` the cw20 pays
+        #[serde(default)]
+        ask_index: u32,
+    },
+}
+
+impl ReceiveMsg {
+    /// Pause flag this message is blocked by
+    #[must_use]
+    pub fn pause_action(&self) -> PauseAction {
+        match self {
+            ReceiveMsg::BuyListingCw20 {
+                ..
+            } => PauseAction::Buy,
+            _ => PauseAction::Create,
+        }
+    }
 }
 
 // cw721 entry point
@@ -100,27 +291,92 @@ pub enum ReceiveNftMsg {
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    GetConfig {},
     #[returns(CountResponse)]
     GetCounts {},
+    /// Live Listings by status & totals from every Trade
+    #[returns(StatsResponse)]
+    GetStats {},
+    /// One page of a per asset total, `start_after` is a denom or contract address
+    #[returns(StatTotalsResponse)]
+    GetStatTotals {
+        total: StatTotal,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
     #[returns(FeeDenomResponse)]
     GetFeeDenom {},
     #[returns(MultiListingResponse)]
-    GetAllListings {},
-    // #[returns(ListingInfoResponse)]
-    // GetListingInfo {
-    //     listing_id: u64,
-    // },
+    GetAllListings {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(ListingResponse)]
+    GetListing {
+        listing_id: u64,
+    },
+    #[returns(BucketResponse)]
+    GetBucket {
+        owner: String,
+        bucket_id: u64,
+    },
     #[returns(MultiListingResponse)]
     GetListingsByOwner {
         owner: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Purchasable Listings whose `BuyerRestriction` names `buyer`
+    #[returns(MultiListingResponse)]
+    GetWhitelistedListings {
+        buyer: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
     },
     #[returns(GetBucketsResponse)]
     GetBuckets {
         bucket_owner: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
     },
-    #[returns(MultiListingResponse)]
+    #[returns(MarketListingsResponse)]
     GetListingsForMarket {
-        page_num: u8,
+        filter: Option<MarketFilter>,
+        sort: Option<MarketSort>,
+        start_after: Option<MarketCursor>,
+        limit: Option<u32>,
+    },
+    /// Completed purchases `seller` sold
+    #[returns(TradesResponse)]
+    GetTradesBySeller {
+        seller: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Completed purchases `buyer` bought
+    #[returns(TradesResponse)]
+    GetTradesByBuyer {
+        buyer: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Completed purchases with an NFT from `collection` on either side
+    #[returns(TradesResponse)]
+    GetTradesByCollection {
+        collection: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Completed purchases made between `from` & `to`, both inclusive & optional
+    ///
+    /// `start_after` is a Trade ID
+    #[returns(TradesResponse)]
+    GetTradesByTime {
+        from: Option<Timestamp>,
+        to: Option<Timestamp>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
     },
 }
 
@@ -131,6 +387,8 @@ pub enum QueryMsg {
 #[cw_serde]
 pub struct CreateListingMsg {
     //pub id: u64,
-    pub ask: GenericBalance,
-    pub whitelisted_buyer: Option<String>,
+    /// Alternative prices, a buyer pays any one of them
+    pub asks: Vec<GenericBalance>,
+    /// Anyone can buy if not set
+    pub buyer_restriction: Option<BuyerRestriction>,
 }
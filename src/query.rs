@@ -4,18 +4,20 @@ use crate::query_imports::*;
 // Queries
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-/// Current Fee Denom
-pub fn get_fee_denom(deps: Deps) -> StdResult<FeeDenomResponse> {
-    let fee_denom: FeeDenom = FEE_DENOM.load(deps.storage)?;
+/// Current Config
+pub fn get_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        config,
+    })
+}
 
-    let symbol = match fee_denom {
-        FeeDenom::JUNO => "JUNO".to_string(),
-        FeeDenom::USDC => "USDC".to_string(),
-    };
+/// Current Fee schedule
+pub fn get_fee_denom(deps: Deps) -> StdResult<FeeDenomResponse> {
+    let fee_config = FEE_CONFIG.load(deps.storage)?;
 
     Ok(FeeDenomResponse {
-        symbol,
-        denom: fee_denom.value(),
+        fee_denoms: fee_config.fee_denoms,
     })
 }
 
@@ -29,99 +31,593 @@ pub fn get_counts(deps: Deps) -> StdResult<CountResponse> {
     })
 }
 
-/// Get all buckets owned by an address
-pub fn get_buckets(deps: Deps, bucket_owner: &str) -> StdResult<GetBucketsResponse> {
+/// Current Stats
+pub fn get_stats(deps: Deps) -> StdResult<StatsResponse> {
+    let stats = STATS.load(deps.storage)?;
+    Ok(StatsResponse {
+        stats,
+    })
+}
+
+/// One page of `total`, ordered by denom or contract address
+pub fn get_stat_totals(
+    deps: Deps,
+    total: StatTotal,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<StatTotalsResponse> {
+    match total {
+        StatTotal::NativeVolume => denom_totals(deps, NATIVE_VOLUME, start_after, limit),
+        StatTotal::Fees => denom_totals(deps, FEE_TOTALS, start_after, limit),
+        StatTotal::Cw20Volume => contract_totals(deps, CW20_VOLUME, start_after, limit),
+        StatTotal::NftsTraded => contract_totals(deps, NFTS_TRADED, start_after, limit),
+    }
+}
+
+fn denom_totals(
+    deps: Deps,
+    totals: Map<&str, Uint128>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<StatTotalsResponse> {
+    let items = totals.range(
+        deps.storage,
+        start_after.as_deref().map(Bound::exclusive),
+        None,
+        Order::Ascending,
+    );
+    let (totals, next) = paginate(items, limit, |(denom, _)| denom.clone())?;
+    Ok(StatTotalsResponse {
+        totals,
+        next,
+    })
+}
+
+fn contract_totals(
+    deps: Deps,
+    totals: Map<&Addr, Uint128>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<StatTotalsResponse> {
+    let start_after = start_after.map(Addr::unchecked);
+    let items = totals
+        .range(deps.storage, start_after.as_ref().map(Bound::exclusive), None, Order::Ascending)
+        .map(|x| x.map(|(contract, total)| (contract.into_string(), total)));
+    let (totals, next) = paginate(items, limit, |(contract, _)| contract.clone())?;
+    Ok(StatTotalsResponse {
+        totals,
+        next,
+    })
+}
+
+/// Single Listing by ID, errors if not found
+pub fn get_listing(deps: Deps, env: &Env, listing_id: u64) -> StdResult<ListingResponse> {
+    let Some(listing) = listingz().may_load(deps.storage, listing_id)? else {
+        return Err(StdError::not_found(format!("Listing {listing_id}")));
+    };
+
+    Ok(ListingResponse {
+        purchasable: listing.is_purchasable(&env.block),
+        listing,
+    })
+}
+
+/// Single Bucket by holder & ID, errors if not found
+///
+/// `owner` is whoever can withdraw the Bucket, the seller once it's paid for a Listing
+pub fn get_bucket(deps: Deps, owner: &str, bucket_id: u64) -> StdResult<BucketResponse> {
+    let owner = deps.api.addr_validate(owner)?;
+
+    let Some(bucket) =
+        bucketz().may_load(deps.storage, bucket_id)?.filter(|b| b.holder() == &owner)
+    else {
+        return Err(StdError::not_found(format!("Bucket {bucket_id}")));
+    };
+
+    Ok(BucketResponse {
+        bucket,
+    })
+}
+
+/// Get buckets held by an address, ordered by Bucket ID
+///
+/// Includes Buckets the address was paid with, not ones it used to buy a Listing
+pub fn get_buckets(
+    deps: Deps,
+    bucket_owner: &str,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<GetBucketsResponse> {
     let bucket_ownerx = deps.api.addr_validate(bucket_owner)?;
+    let min = start_after.map(Bound::exclusive);
 
-    let user_bucks: StdResult<Vec<_>> =
-        BUCKETS.prefix(bucket_ownerx).range(deps.storage, None, None, Order::Ascending).collect();
+    let claimed = bucketz().idx.claimant.prefix(bucket_ownerx.clone()).range(
+        deps.storage,
+        min.clone(),
+        None,
+        Order::Ascending,
+    );
+    let unclaimed = bucketz()
+        .idx
+        .owner
+        .prefix(bucket_ownerx)
+        .range(deps.storage, min, None, Order::Ascending)
+        .filter(|x| x.as_ref().map_or(true, |(_, b)| b.claimant.is_none()));
+
+    let user_bucks = merge_by_id(claimed, unclaimed, limit)?;
+
+    let (buckets, next) = paginate(user_bucks.into_iter().map(Ok), limit, |(id, _)| *id)?;
 
     Ok(GetBucketsResponse {
-        buckets: user_bucks?,
+        buckets,
+        next,
     })
 }
 
-/// Get all listings owned by an Address
-pub fn get_listings_by_owner(deps: Deps, owner: &str) -> StdResult<MultiListingResponse> {
+/// Get listings held by an Address, ordered by Listing ID
+///
+/// Includes Listings the address bought, not ones it created that have been bought
+pub fn get_listings_by_owner(
+    deps: Deps,
+    owner: &str,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<MultiListingResponse> {
     let owner = deps.api.addr_validate(owner)?;
+    let min = start_after.map(Bound::exclusive);
 
-    let all_listings: StdResult<Vec<_>> =
-        listingz().prefix(&owner).range(deps.storage, None, None, Order::Ascending).collect();
+    let claimed = listingz().idx.claimant.prefix(owner.clone()).range(
+        deps.storage,
+        min.clone(),
+        None,
+        Order::Ascending,
+    );
+    let unclaimed = listingz()
+        .idx
+        .creator
+        .prefix(owner)
+        .range(deps.storage, min, None, Order::Ascending)
+        .filter(|x| x.as_ref().map_or(true, |(_, l)| l.claimant.is_none()));
+
+    let owner_listings = merge_by_id(claimed, unclaimed, limit)?;
 
-    let listing_data: Vec<Listing> = all_listings?.iter().map(|tup| tup.1.clone()).collect();
+    let (listings, next) =
+        paginate(owner_listings.into_iter().map(|(_, l)| Ok(l)), limit, |l| l.id)?;
 
     Ok(MultiListingResponse {
-        listings: listing_data,
+        listings,
+        next,
     })
 }
 
-// XXXXXXXXXXXXXXXXXXXXXXX needs check
-/// Finds all listings that `owner` is whitelisted to purchase
-pub fn get_users_whitelisted_listings(deps: Deps, owner: &str) -> StdResult<MultiListingResponse> {
-    let search_whitelists: Vec<_> = listingz()
+/// Purchasable listings whose `BuyerRestriction` names `buyer`, ordered by Listing ID
+///
+/// Listings restricted to a cw4 group or NFT holders aren't included
+pub fn get_whitelisted_listings(
+    deps: Deps,
+    env: &Env,
+    buyer: &str,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<MultiListingResponse> {
+    let buyer = deps.api.addr_validate(buyer)?;
+
+    let reserved = listingz()
         .idx
-        .whitelisted_buyer
-        .prefix(owner.to_string())
-        .range(deps.storage, None, None, Order::Ascending)
-        .collect::<StdResult<Vec<_>>>() // StdResult<Vec<(PK, Listing)>>
-        .unwrap_or_default()
-        .iter()
-        .map(|entry| entry.1.clone())
-        .collect();
+        .buyer
+        .prefix(buyer)
+        .keys(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+        .map(|id| id.and_then(|id| listingz().load(deps.storage, id)))
+        .filter(|x| x.as_ref().map_or(true, |l| l.is_purchasable(&env.block)));
+
+    let (listings, next) = paginate(reserved, limit, |l| l.id)?;
 
     Ok(MultiListingResponse {
-        listings: search_whitelists,
+        listings,
+        next,
     })
 }
 
-pub fn get_all_listings(deps: Deps) -> StdResult<MultiListingResponse> {
-    let all_listings: StdResult<Vec<_>> =
-        listingz().range(deps.storage, None, None, Order::Ascending).take(100).collect();
+/// Get all listings, ordered by Listing ID
+pub fn get_all_listings(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<MultiListingResponse> {
+    let all_listings =
+        listingz().range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending);
 
-    let listing_data: Vec<Listing> = all_listings?.iter().map(|entry| entry.1.clone()).collect();
+    let (listings, next) = paginate(all_listings.map(|x| x.map(|(_, l)| l)), limit, |l| l.id)?;
 
     Ok(MultiListingResponse {
-        listings: listing_data,
+        listings,
+        next,
     })
 }
 
-// Query w filter & pagination
+/// Browse Listings with `filter`, ordered by `sort`
+///
+/// `start_after` is the `next` cursor of the previous page, at most `MAX_MARKET_SCAN` Listings are read
 pub fn get_listings_for_market(
     deps: Deps,
     env: &Env,
-    page_num: u8,
-) -> StdResult<MultiListingResponse> {
-    let current_time = env.block.time.seconds();
-    let two_weeks_ago_in_seconds = current_time - 1_209_600;
+    filter: Option<MarketFilter>,
+    sort: Option<MarketSort>,
+    start_after: Option<MarketCursor>,
+    limit: Option<u32>,
+) -> StdResult<MarketListingsResponse> {
+    let filter = filter.unwrap_or_default();
+    let whitelisted_for =
+        filter.whitelisted_for.as_ref().map(|a| deps.api.addr_validate(a)).transpose()?;
+    let block = &env.block;
 
-    let to_skip_usize = usize::from(page_num * 20 - 20);
+    let listings: Box<dyn Iterator<Item = StdResult<Listing>>> = match sort.unwrap_or_default() {
+        MarketSort::Newest => Box::new(
+            listingz()
+                .range(
+                    deps.storage,
+                    None,
+                    start_after.map(|c| Bound::exclusive(c.id)),
+                    Order::Descending,
+                )
+                .map(|x| x.map(|(_, l)| l)),
+        ),
+        MarketSort::SoonestExpiring => {
+            // The cursor holds the index key, so the Listing it came from doesn't have to exist
+            let min = match start_after {
+                Some(cursor) => Bound::exclusive((cursor.expiration, cursor.id)),
+                // Skip time based expirations that have already passed
+                None if !filter.include_expired => Bound::inclusive(((0, block.time.seconds()), 0)),
+                None => Bound::inclusive(((0, 0), 0)),
+            };
+            Box::new(
+                listingz()
+                    .idx
+                    .expiration
+                    .range(deps.storage, Some(min), None, Order::Ascending)
+                    .map(|x| x.map(|(_, l)| l)),
+            )
+        }
+    };
 
-    let listings_in_range: Vec<_> = listingz()
-        .idx
-        .finalized_date
-        .prefix_range_raw(
-            deps.storage,
-            Some(PrefixBound::inclusive(two_weeks_ago_in_seconds)),
-            None,
-            Order::Ascending,
-        )
-        .collect::<StdResult<Vec<_>>>()
-        .unwrap_or_default()
-        .iter()
-        .skip(to_skip_usize)
-        .take(20)
-        .map(|entry| entry.1.clone())
-        .collect();
+    // Stop after `MAX_MARKET_SCAN` Listings so a narrow filter can't run out of gas
+    let mut scanned = 0;
+    let mut last_scanned = None;
+    let matching = listings
+        .take(MAX_MARKET_SCAN as usize)
+        .inspect(|x| {
+            scanned += 1;
+            last_scanned = x.as_ref().ok().map(MarketCursor::new);
+        })
+        .filter(|x| match x {
+            Ok(listing) => filter.matches(listing, block, whitelisted_for.as_ref()),
+            Err(_) => true,
+        });
 
-    Ok(MultiListingResponse {
-        listings: listings_in_range,
+    let (listings, mut next) = paginate(matching, limit, MarketCursor::new)?;
+
+    // Scan cut short before the page filled up, carry on from the last Listing read
+    if next.is_none() && scanned == MAX_MARKET_SCAN {
+        next = last_scanned;
+    }
+
+    Ok(MarketListingsResponse {
+        listings,
+        next,
+    })
+}
+
+/// Trades sold by `seller`, ordered by Trade ID
+pub fn get_trades_by_seller(
+    deps: Deps,
+    seller: &str,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<TradesResponse> {
+    let seller = deps.api.addr_validate(seller)?;
+
+    let sold = trades().idx.seller.prefix(seller).range(
+        deps.storage,
+        start_after.map(Bound::exclusive),
+        None,
+        Order::Ascending,
+    );
+
+    trades_page(sold.map(|x| x.map(|(_, t)| t)), limit)
+}
+
+/// Trades bought by `buyer`, ordered by Trade ID
+pub fn get_trades_by_buyer(
+    deps: Deps,
+    buyer: &str,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<TradesResponse> {
+    let buyer = deps.api.addr_validate(buyer)?;
+
+    let bought = trades().idx.buyer.prefix(buyer).range(
+        deps.storage,
+        start_after.map(Bound::exclusive),
+        None,
+        Order::Ascending,
+    );
+
+    trades_page(bought.map(|x| x.map(|(_, t)| t)), limit)
+}
+
+/// Trades with an NFT from `collection` on either side, ordered by Trade ID
+pub fn get_trades_by_collection(
+    deps: Deps,
+    collection: &str,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<TradesResponse> {
+    let collection = deps.api.addr_validate(collection)?;
+
+    let traded = TRADE_COLLECTIONS
+        .prefix(&collection)
+        .keys(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+        .map(|id| id.and_then(|id| trades().load(deps.storage, id)));
+
+    trades_page(traded, limit)
+}
+
+/// Trades made between `from` & `to` (inclusive), ordered by time then Trade ID
+pub fn get_trades_by_time(
+    deps: Deps,
+    from: Option<Timestamp>,
+    to: Option<Timestamp>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<TradesResponse> {
+    let min = match start_after {
+        Some(id) => {
+            let Some(cursor) = trades().may_load(deps.storage, id)? else {
+                return Err(StdError::not_found(format!("Trade {id}")));
+            };
+            Bound::exclusive((cursor.time.seconds(), id))
+        }
+        None => Bound::inclusive((from.map_or(0, |t| t.seconds()), 0)),
+    };
+    let max = to.map(|t| Bound::inclusive((t.seconds(), u64::MAX)));
+
+    let in_range = trades().idx.time.range(deps.storage, Some(min), max, Order::Ascending);
+
+    trades_page(in_range.map(|x| x.map(|(_, t)| t)), limit)
+}
+
+fn trades_page(
+    trades: impl Iterator<Item = StdResult<Trade>>,
+    limit: Option<u32>,
+) -> StdResult<TradesResponse> {
+    let (trades, next) = paginate(trades, limit, |t| t.id)?;
+
+    Ok(TradesResponse {
+        trades,
+        next,
     })
 }
 
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Stat Totals
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Per asset totals kept alongside `Stats`
+#[cw_serde]
+pub enum StatTotal {
+    /// Natives exchanged on both sides of every Trade, before fees, by denom
+    NativeVolume,
+    /// cw20s exchanged on both sides of every Trade, by contract
+    Cw20Volume,
+    /// NFTs exchanged on both sides of every Trade, by cw721 contract
+    NftsTraded,
+    /// Trade & cancel fees, by denom
+    Fees,
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Market Filters
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cw_serde]
+#[derive(Default)]
+pub enum MarketSort {
+    /// Most recently created first
+    #[default]
+    Newest,
+    /// Closest to expiring first, time based expirations before height based ones
+    ///
+    /// Listings that never expire or aren't finalized come last
+    SoonestExpiring,
+}
+
+/// Position of the last Listing on a `GetListingsForMarket` page
+///
+/// Holds the Listing's sort keys, so paging carries on if that Listing is bought, removed or changed
+#[cw_serde]
+pub struct MarketCursor {
+    pub id: u64,
+    /// `expiration_key` of the Listing, only used by `MarketSort::SoonestExpiring`
+    pub expiration: (u8, u64),
+}
+
+impl MarketCursor {
+    #[must_use]
+    pub fn new(listing: &Listing) -> Self {
+        MarketCursor {
+            id: listing.id,
+            expiration: expiration_key(listing.expiration.as_ref()),
+        }
+    }
+}
+
+/// Every set field must match
+///
+/// When no filter is given, only Listings that are `FinalizedReady`, started & not expired are returned
+#[cw_serde]
+pub struct MarketFilter {
+    /// `None` for any status
+    pub status: Option<Status>,
+    #[serde(default)]
+    pub include_expired: bool,
+    /// Only Listings whose `start` hasn't been reached, instead of ones that have
+    #[serde(default)]
+    pub upcoming: bool,
+    /// Only Listings this address can buy (no buyer restriction, or one that names them)
+    ///
+    /// Listings restricted to a cw4 group or NFT holders aren't included
+    pub whitelisted_for: Option<String>,
+    /// Listings selling this asset
+    pub for_sale_contains: Option<AssetFilter>,
+    /// Listings with any ask asking for this asset
+    pub ask_contains: Option<AssetFilter>,
+}
+
+impl Default for MarketFilter {
+    fn default() -> Self {
+        MarketFilter {
+            status: Some(Status::FinalizedReady),
+            include_expired: false,
+            upcoming: false,
+            whitelisted_for: None,
+            for_sale_contains: None,
+            ask_contains: None,
+        }
+    }
+}
+
+impl MarketFilter {
+    #[must_use]
+    pub fn matches(
+        &self,
+        listing: &Listing,
+        block: &BlockInfo,
+        whitelisted_for: Option<&Addr>,
+    ) -> bool {
+        self.status.as_ref().map_or(true, |s| s == &listing.status)
+            && (self.include_expired || !listing.is_expired(block))
+            && listing.has_started(block) != self.upcoming
+            && whitelisted_for.map_or(true, |addr| {
+                listing.buyer_restriction.as_ref().map_or(true, |r| r.names(addr))
+            })
+            && self.for_sale_contains.as_ref().map_or(true, |a| a.is_in(&listing.for_sale))
+            && self
+                .ask_contains
+                .as_ref()
+                .map_or(true, |a| listing.asks.iter().any(|ask| a.is_in(ask)))
+    }
+}
+
+#[cw_serde]
+pub enum AssetFilter {
+    Native {
+        denom: String,
+    },
+    Cw20 {
+        address: String,
+    },
+    /// Any NFT from `contract_address`, or a specific one if `token_id` is set
+    Nft {
+        contract_address: String,
+        token_id: Option<String>,
+    },
+}
+
+impl AssetFilter {
+    /// `true` if any amount of this asset is in `balance`
+    #[must_use]
+    pub fn is_in(&self, balance: &GenericBalance) -> bool {
+        match self {
+            AssetFilter::Native {
+                denom,
+            } => balance.native.iter().any(|c| &c.denom == denom),
+            AssetFilter::Cw20 {
+                address,
+            } => balance.cw20.iter().any(|c| c.address.as_str() == address),
+            AssetFilter::Nft {
+                contract_address,
+                token_id,
+            } => balance.nfts.iter().any(|n| {
+                n.contract_address.as_str() == contract_address
+                    && token_id.as_ref().map_or(true, |id| &n.token_id == id)
+            }),
+        }
+    }
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Pagination
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Page size when `limit` isn't given
+pub const DEFAULT_LIMIT: u32 = 30;
+
+/// Largest page size allowed, higher limits are capped to this
+pub const MAX_LIMIT: u32 = 100;
+
+/// Most Listings `GetListingsForMarket` reads per query, whether they match or not
+///
+/// A page can come back short (or empty) with `next` set once this is reached
+pub const MAX_MARKET_SCAN: u32 = 300;
+
+/// Takes one page of `items`
+///
+/// Returns the page & the cursor to pass as `start_after` for the next page,
+/// which is `None` once the last page has been reached
+pub fn paginate<T, C>(
+    items: impl Iterator<Item = StdResult<T>>,
+    limit: Option<u32>,
+    cursor: impl Fn(&T) -> C,
+) -> StdResult<(Vec<T>, Option<C>)> {
+    let limit = page_size(limit);
+
+    // Take 1 extra to see if there's another page
+    let mut page = items.take(limit + 1).collect::<StdResult<Vec<T>>>()?;
+
+    let next = if page.len() > limit {
+        page.truncate(limit);
+        page.last().map(cursor)
+    } else {
+        None
+    };
+
+    Ok((page, next))
+}
+
+/// `limit` capped to `MAX_LIMIT`, `DEFAULT_LIMIT` if not set
+fn page_size(limit: Option<u32>) -> usize {
+    limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT) as usize
+}
+
+/// Merges two id ordered ranges into one, keeping enough entries for `paginate` to take a page
+fn merge_by_id<T>(
+    one: impl Iterator<Item = StdResult<(u64, T)>>,
+    two: impl Iterator<Item = StdResult<(u64, T)>>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(u64, T)>> {
+    let take = page_size(limit) + 1;
+
+    let mut merged = one.take(take).chain(two.take(take)).collect::<StdResult<Vec<_>>>()?;
+    merged.sort_by_key(|(id, _)| *id);
+    merged.truncate(take);
+
+    Ok(merged)
+}
+
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // Responses
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+#[cw_serde]
+pub struct ConfigResponse {
+    pub config: Config,
+}
+
+#[cw_serde]
+pub struct BucketResponse {
+    pub bucket: Bucket,
+}
+
 #[cw_serde]
 pub struct CountResponse {
     pub bucket_count: u64,
@@ -130,16 +626,53 @@ pub struct CountResponse {
 
 #[cw_serde]
 pub struct FeeDenomResponse {
-    pub symbol: String,
-    pub denom: String,
+    pub fee_denoms: Vec<FeeDenom>,
 }
 
 #[cw_serde]
 pub struct GetBucketsResponse {
     pub buckets: Vec<(u64, Bucket)>,
+    /// `start_after` for the next page, `None` if this is the last page
+    pub next: Option<u64>,
+}
+
+#[cw_serde]
+pub struct StatsResponse {
+    pub stats: Stats,
+}
+
+#[cw_serde]
+pub struct MarketListingsResponse {
+    pub listings: Vec<Listing>,
+    /// `start_after` for the next page, `None` if this is the last page
+    pub next: Option<MarketCursor>,
+}
+
+#[cw_serde]
+pub struct StatTotalsResponse {
+    /// (denom or contract, total)
+    pub totals: Vec<(String, Uint128)>,
+    /// `start_after` for the next page, `None` if this is the last page
+    pub next: Option<String>,
+}
+
+#[cw_serde]
+pub struct TradesResponse {
+    pub trades: Vec<Trade>,
+    /// `start_after` for the next page, `None` if this is the last page
+    pub next: Option<u64>,
+}
+
+#[cw_serde]
+pub struct ListingResponse {
+    pub listing: Listing,
+    /// Finalized, not yet purchased & not expired
+    pub purchasable: bool,
 }
 
 #[cw_serde]
 pub struct MultiListingResponse {
     pub listings: Vec<Listing>,
+    /// `start_after` for the next page, `None` if this is the last page
+    pub next: Option<u64>,
 }
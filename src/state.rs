@@ -9,24 +9,362 @@ pub const LISTING_COUNT: Item<u64> = Item::new("listing_count");
 
 pub const BUCKET_COUNT: Item<u64> = Item::new("bucket_count");
 
-//pub const DAO: Item<Addr> = Item::new("DAO");
+pub const TRADE_COUNT: Item<u64> = Item::new("trade_count");
 
-pub const FEE_DENOM: Item<FeeDenom> = Item::new("fee_denom");
+pub const STATS: Item<Stats> = Item::new("stats");
+
+/// Natives exchanged on both sides of every Trade, before fees, by denom
+pub const NATIVE_VOLUME: Map<&str, Uint128> = Map::new("stats__native_volume");
+
+/// cw20s exchanged on both sides of every Trade, by contract
+pub const CW20_VOLUME: Map<&Addr, Uint128> = Map::new("stats__cw20_volume");
+
+/// NFTs exchanged on both sides of every Trade, by cw721 contract
+pub const NFTS_TRADED: Map<&Addr, Uint128> = Map::new("stats__nfts_traded");
+
+/// Trade & cancel fees, by denom
+pub const FEE_TOTALS: Map<&str, Uint128> = Map::new("stats__fees");
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+#[cw_serde]
+pub struct Config {
+    pub admin: Addr,
+    /// Set by `ProposeAdmin`, becomes `admin` once they call `AcceptAdmin`
+    pub pending_admin: Option<Addr>,
+    pub fee_recipient: FeeRecipient,
+    pub limits: Limits,
+    pub pause: Pause,
+    /// Which tokens can be traded
+    pub token_lists: TokenLists,
+}
+
+#[cw_serde]
+pub enum FeeRecipient {
+    CommunityPool,
+    Address(Addr),
+}
+
+impl FeeRecipient {
+    /// Errors if `FeeRecipient::Address` is not a valid address
+    pub fn validate(self, api: &dyn Api) -> Result<FeeRecipient, ContractError> {
+        match self {
+            FeeRecipient::CommunityPool => Ok(FeeRecipient::CommunityPool),
+            FeeRecipient::Address(addr) => api
+                .addr_validate(addr.as_str())
+                .map(FeeRecipient::Address)
+                .map_err(|_| ContractError::InvalidAddressFormat),
+        }
+    }
+
+    /// Returns a `CosmosMsg` paying `fees` out to this recipient
+    ///
+    /// `contract` is this contract's address, used as the Com. Pool depositor
+    pub fn fee_msg(&self, fees: &[Coin], contract: &Addr) -> Result<CosmosMsg, ContractError> {
+        match self {
+            FeeRecipient::CommunityPool => fees.get_cp_msg(contract),
+            FeeRecipient::Address(addr) => Ok(CosmosMsg::from(BankMsg::Send {
+                to_address: addr.to_string(),
+                amount: fees.to_vec(),
+            })),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct Limits {
+    /// Max number of distinct Natives + CW20s + NFTs in a `for_sale`, ask or Bucket
+    pub max_assets: u32,
+    /// Max number of alternative asks on a Listing
+    pub max_asks: u32,
+    /// Seconds a finalized Listing must be live before `CancelListing` is allowed
+    pub min_cancel_seconds: u64,
+    /// If true, `CancelListing` charges the market fee on the fee denoms in `for_sale`
+    pub cancel_fee: bool,
+    /// Max number of addresses in `BuyerRestriction::Addresses`
+    pub max_buyers: u32,
+    /// How far ahead a Listing's expiration can be set
+    pub expiration: ExpirationLimits,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_assets: 50,
+            max_asks: 10,
+            min_cancel_seconds: 600,
+            cancel_fee: false,
+            max_buyers: 50,
+            expiration: ExpirationLimits::default(),
+        }
+    }
+}
+
+impl Limits {
+    pub fn check_valid(&self) -> Result<(), ContractError> {
+        if self.max_assets == 0 {
+            return Err(ContractError::GenericError("max_assets cannot be 0".to_string()));
+        }
+        if self.max_asks == 0 {
+            return Err(ContractError::GenericError("max_asks cannot be 0".to_string()));
+        }
+        self.expiration.check_valid()
+    }
+
+    /// Errors if there are no asks, more than `max_asks`, or any ask is invalid
+    /// or over `max_assets`
+    ///
+    /// Queries every cw20 & cw721 contract in the asks
+    pub fn check_asks(&self, deps: Deps, asks: &[GenericBalance]) -> Result<(), ContractError> {
+        if asks.is_empty() || asks.len() > self.max_asks as usize {
+            return Err(ContractError::GenericError(format!(
+                "Must have between 1 and {} asks",
+                self.max_asks
+            )));
+        }
+        for ask in asks {
+            ask.check_valid(deps)?;
+            ask.check_limits(self)?;
+        }
+        Ok(())
+    }
+}
+
+#[cw_serde]
+pub struct ExpirationLimits {
+    /// Bounds for `Expiration::AtTime`, in seconds from the current block
+    pub min_seconds: u64,
+    pub max_seconds: u64,
+    /// Bounds for `Expiration::AtHeight`, in blocks from the current block
+    pub min_blocks: u64,
+    pub max_blocks: u64,
+    /// If true, Listings can use `Expiration::Never`
+    pub allow_never: bool,
+}
+
+impl Default for ExpirationLimits {
+    fn default() -> Self {
+        ExpirationLimits {
+            // 10 minutes
+            min_seconds: 600,
+            // 14 days
+            max_seconds: 1_209_600,
+            // ~10 minutes at 6 second blocks
+            min_blocks: 100,
+            // ~14 days at 6 second blocks
+            max_blocks: 201_600,
+            allow_never: false,
+        }
+    }
+}
+
+impl ExpirationLimits {
+    pub fn check_valid(&self) -> Result<(), ContractError> {
+        if self.min_seconds > self.max_seconds || self.min_blocks > self.max_blocks {
+            return Err(ContractError::GenericError(
+                "Expiration minimum cannot be greater than maximum".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Errors if `expiration` is not within these bounds from `block`
+    pub fn check(&self, expiration: &Expiration, block: &BlockInfo) -> Result<(), ContractError> {
+        let in_bounds = match expiration {
+            Expiration::AtTime(time) => time
+                .seconds()
+                .checked_sub(block.time.seconds())
+                .map_or(false, |secs| (self.min_seconds..=self.max_seconds).contains(&secs)),
+            Expiration::AtHeight(height) => height
+                .checked_sub(block.height)
+                .map_or(false, |blocks| (self.min_blocks..=self.max_blocks).contains(&blocks)),
+            Expiration::Never {} => self.allow_never,
+        };
+        if !in_bounds {
+            return Err(ContractError::InvalidExpiration {});
+        }
+        Ok(())
+    }
+}
+
+/// Circuit breaker, each flag blocks one group of `ExecuteMsg`s
+///
+/// Pausing `create` & `buy` only leaves the market in withdraw-only mode
+#[cw_serde]
+#[derive(Default)]
+pub struct Pause {
+    /// Creating / editing / finalizing Listings & Buckets
+    pub create: bool,
+    /// `BuyListing`
+    pub buy: bool,
+    /// Taking assets out of Buckets & Listings, ie `RemoveBucket`, `RemoveFromListing`,
+    /// `DeleteListing` & `CancelListing`
+    pub withdraw: bool,
+}
+
+#[cw_serde]
+pub enum PauseAction {
+    Create,
+    Buy,
+    Withdraw,
+}
+
+impl Pause {
+    /// Errors if `action` is currently paused
+    pub fn check(&self, action: &PauseAction) -> Result<(), ContractError> {
+        let paused = match action {
+            PauseAction::Create => self.create,
+            PauseAction::Buy => self.buy,
+            PauseAction::Withdraw => self.withdraw,
+        };
+        if paused {
+            return Err(ContractError::Paused {
+                action: format!("{action:?}"),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Admin managed lists of natives, cw20s & cw721s, `mode` decides how they're used
+#[cw_serde]
+#[derive(Default)]
+pub struct TokenLists {
+    pub mode: ListMode,
+    pub natives: Vec<String>,
+    pub cw20s: Vec<Addr>,
+    pub cw721s: Vec<Addr>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum ListMode {
+    /// Any token can be traded, the lists are ignored
+    #[default]
+    Open,
+    /// Only listed tokens can be traded
+    Allow,
+    /// Listed tokens can't be traded
+    Deny,
+}
+
+impl TokenLists {
+    /// Errors if any cw20 or cw721 address is invalid
+    pub fn validate(self, api: &dyn Api) -> Result<TokenLists, ContractError> {
+        let validate = |addrs: Vec<Addr>| {
+            addrs
+                .into_iter()
+                .map(|addr| api.addr_validate(addr.as_str()))
+                .collect::<Result<Vec<Addr>, _>>()
+                .map_err(|_| ContractError::InvalidAddressFormat)
+        };
+        Ok(TokenLists {
+            cw20s: validate(self.cw20s)?,
+            cw721s: validate(self.cw721s)?,
+            ..self
+        })
+    }
+
+    fn allows(&self, listed: bool, token: &str) -> Result<(), ContractError> {
+        let allowed = match self.mode {
+            ListMode::Open => true,
+            ListMode::Allow => listed,
+            ListMode::Deny => !listed,
+        };
+        if !allowed {
+            return Err(ContractError::TokenNotAllowed(token.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Errors if native `denom` can't be traded
+    pub fn check_native(&self, denom: &str) -> Result<(), ContractError> {
+        self.allows(self.natives.iter().any(|n| n == denom), denom)
+    }
+
+    /// Errors if cw20 contract `addr` can't be traded
+    pub fn check_cw20(&self, addr: &Addr) -> Result<(), ContractError> {
+        self.allows(self.cw20s.contains(addr), addr.as_str())
+    }
+
+    /// Errors if cw721 collection `addr` can't be traded
+    pub fn check_cw721(&self, addr: &Addr) -> Result<(), ContractError> {
+        self.allows(self.cw721s.contains(addr), addr.as_str())
+    }
+
+    /// Errors if any token in `balance` can't be traded
+    pub fn check(&self, balance: &GenericBalance) -> Result<(), ContractError> {
+        for coin in &balance.native {
+            self.check_native(&coin.denom)?;
+        }
+        for cw in &balance.cw20 {
+            self.check_cw20(&cw.address)?;
+        }
+        for nft in &balance.nfts {
+            self.check_cw721(&nft.contract_address)?;
+        }
+        Ok(())
+    }
+}
+
+pub const FEE_CONFIG: Item<FeeConfig> = Item::new("fee_config");
+
+/// 100% in basis points
+pub const MAX_FEE_BPS: u64 = 10_000;
+
+#[cw_serde]
+pub struct FeeConfig {
+    pub fee_denoms: Vec<FeeDenom>,
+}
 
 #[cw_serde]
-pub enum FeeDenom {
-    JUNO,
-    USDC,
+pub struct FeeDenom {
+    /// Native denom fees are taken in, ie `"ujuno"`
+    pub denom: String,
+    /// Fee rate in basis points (50 = 0.5%)
+    pub bps: u64,
+    /// If set, fees below this amount are rounded up to it
+    pub min_fee: Option<Uint128>,
 }
 
-impl FeeDenom {
-    pub fn value(&self) -> String {
-        match *self {
-            FeeDenom::JUNO => "ujunox".to_string(),
-            //FeeDenom::JUNO => "ujuno".to_string(),
-            FeeDenom::USDC => "uusdcx".to_string(),
-            //FeeDenom::USDC => "ibc/EAC38D55372F38F1AFD68DF7FE9EF762DCF69F26520643CF3F9D292A738D8034".to_string()
+impl FeeConfig {
+    /// Errors if any are true:
+    ///
+    /// - Any denom is empty
+    /// - Any duplicate denom
+    /// - Any `bps` > `MAX_FEE_BPS`
+    /// - Any `min_fee` == 0
+    pub fn check_valid(&self) -> Result<(), ContractError> {
+        if self.fee_denoms.iter().any(|f| f.denom.trim().is_empty()) {
+            return Err(ContractError::InvalidFeeConfig("Empty denom".to_string()));
+        }
+
+        let denom_bt = self
+            .fee_denoms
+            .iter()
+            .map(|f| (f.denom.clone(), 1u8))
+            .collect::<BTreeMap<String, u8>>();
+        if denom_bt.len() != self.fee_denoms.len() {
+            return Err(ContractError::InvalidFeeConfig("Duplicate denom".to_string()));
+        }
+
+        if self.fee_denoms.iter().any(|f| f.bps > MAX_FEE_BPS) {
+            return Err(ContractError::InvalidFeeConfig(format!(
+                "Rate cannot exceed {} bps",
+                MAX_FEE_BPS
+            )));
+        }
+
+        if self.fee_denoms.iter().any(|f| f.min_fee.map_or(false, |m| m.is_zero())) {
+            return Err(ContractError::InvalidFeeConfig("Minimum fee cannot be 0".to_string()));
         }
+
+        Ok(())
+    }
+
+    /// Find the schedule entry for `denom`, if it's a fee denom
+    pub fn get(&self, denom: &str) -> Option<&FeeDenom> {
+        self.fee_denoms.iter().find(|f| f.denom == denom)
     }
 }
 
@@ -36,44 +374,114 @@ impl FeeDenom {
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 pub struct ListingIndexes<'a> {
-    pub id: UniqueIndex<'a, u64, Listing, (&'a Addr, u64)>,
-    pub finalized_date: MultiIndex<'a, u64, Listing, (&'a Addr, u64)>,
-    // (whitelisted_buyer/default, listing_id as u64)  |
-    pub whitelisted_buyer: UniqueIndex<'a, (String, u64), Listing, (&'a Addr, u64)>,
+    pub creator: MultiIndex<'a, Addr, Listing, u64>,
+    // Empty address if the Listing hasn't been purchased
+    pub claimant: MultiIndex<'a, Addr, Listing, u64>,
+    pub finalized_date: MultiIndex<'a, u64, Listing, u64>,
+    // (0 + seconds if AtTime / 1 + height if AtHeight / 2 if Never or not finalized)
+    pub expiration: MultiIndex<'a, (u8, u64), Listing, u64>,
+    // (buyer, listing_id) for every address named by `BuyerRestriction::Address(es)`
+    pub buyer: BuyerIndex<'a>,
 }
 
 impl IndexList<Listing> for ListingIndexes<'_> {
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Listing>> + '_> {
-        let v: Vec<&dyn Index<Listing>> =
-            vec![&self.id, &self.finalized_date, &self.whitelisted_buyer];
+        let v: Vec<&dyn Index<Listing>> = vec![
+            &self.creator,
+            &self.claimant,
+            &self.finalized_date,
+            &self.expiration,
+            &self.buyer,
+        ];
         Box::new(v.into_iter())
     }
 }
 
+/// Listing IDs by the buyers their `BuyerRestriction` names
+///
+/// A `MultiIndex` holds one key per Listing, `BuyerRestriction::Addresses` needs one per address.
+/// Group & NFT holder restrictions name no one, so they aren't indexed
+pub struct BuyerIndex<'a> {
+    listings: Map<'a, (Addr, u64), Empty>,
+}
+
+impl<'a> BuyerIndex<'a> {
+    #[must_use]
+    pub const fn new(namespace: &'a str) -> Self {
+        BuyerIndex {
+            listings: Map::new(namespace),
+        }
+    }
+
+    /// IDs of the Listings naming `buyer`
+    #[must_use]
+    pub fn prefix(&self, buyer: Addr) -> Prefix<u64, Empty, u64> {
+        self.listings.prefix(buyer)
+    }
+}
+
+impl Index<Listing> for BuyerIndex<'_> {
+    fn save(&self, store: &mut dyn Storage, _pk: &[u8], data: &Listing) -> StdResult<()> {
+        for buyer in data.buyer_restriction.iter().flat_map(BuyerRestriction::named) {
+            self.listings.save(store, (buyer.clone(), data.id), &Empty {})?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, store: &mut dyn Storage, _pk: &[u8], old_data: &Listing) -> StdResult<()> {
+        for buyer in old_data.buyer_restriction.iter().flat_map(BuyerRestriction::named) {
+            self.listings.remove(store, (buyer.clone(), old_data.id));
+        }
+        Ok(())
+    }
+}
+
+/// Listings keyed by `Listing.id`, which doesn't change when the Listing is purchased
 #[must_use]
-pub fn listingz<'a>() -> IndexedMap<'a, (&'a Addr, u64), Listing, ListingIndexes<'a>> {
+pub fn listingz<'a>() -> IndexedMap<'a, u64, Listing, ListingIndexes<'a>> {
     let indexes = ListingIndexes {
-        id: UniqueIndex::new(|a_listing| a_listing.id, "listing__id"),
+        creator: MultiIndex::new(
+            |_pk, a_listing| a_listing.creator.clone(),
+            "listings",
+            "listings__creator",
+        ),
+        claimant: MultiIndex::new(
+            |_pk, a_listing| claimant_key(a_listing.claimant.as_ref()),
+            "listings",
+            "listings__claimant",
+        ),
         finalized_date: MultiIndex::new(
             |_pk, a_listing| a_listing.finalized_time.map_or(0_u64, |x| x.seconds()),
-            "listings_im",
-            "listing__finalized__date",
+            "listings",
+            "listings__finalized__date",
         ),
-        whitelisted_buyer: UniqueIndex::new(
-            |listing| {
-                (
-                    listing
-                        .whitelisted_buyer
-                        .clone()
-                        .map_or_else(|| "1".to_string(), |addr| addr.to_string()),
-                    listing.id,
-                )
-            },
-            "listing__whitelisted__buyer",
+        expiration: MultiIndex::new(
+            |_pk, listing| expiration_key(listing.expiration.as_ref()),
+            "listings",
+            "listings__expiration",
         ),
+        buyer: BuyerIndex::new("listings__buyer"),
     };
 
-    IndexedMap::new("listings_im", indexes)
+    IndexedMap::new("listings", indexes)
+}
+
+/// Key in the `claimant` indexes, an empty address when there's no claimant
+#[must_use]
+pub fn claimant_key(claimant: Option<&Addr>) -> Addr {
+    claimant.cloned().unwrap_or_else(|| Addr::unchecked(""))
+}
+
+/// Key in `ListingIndexes.expiration`
+///
+/// Time based expirations sort first, then height based, then Listings that never expire
+#[must_use]
+pub fn expiration_key(expiration: Option<&Expiration>) -> (u8, u64) {
+    match expiration {
+        Some(Expiration::AtTime(time)) => (0, time.seconds()),
+        Some(Expiration::AtHeight(height)) => (1, *height),
+        Some(Expiration::Never {}) | None => (2, 0),
+    }
 }
 
 #[cw_serde]
@@ -82,45 +490,85 @@ pub struct Listing {
     pub id: u64,
 
     pub finalized_time: Option<Timestamp>,
-    pub expiration_time: Option<Timestamp>,
+    /// If set, the Listing can't be bought until then
+    pub start: Option<Scheduled>,
+    /// `None` until finalized
+    pub expiration: Option<Expiration>,
     pub status: Status,
 
     pub claimant: Option<Addr>,
-    pub whitelisted_buyer: Option<Addr>,
+    /// Anyone can buy if not set
+    pub buyer_restriction: Option<BuyerRestriction>,
 
     pub for_sale: GenericBalance,
-    pub ask: GenericBalance,
+    /// Any one of these is accepted as payment
+    pub asks: Vec<GenericBalance>,
 
-    pub fee_amount: Option<Coin>,
+    pub fee_amount: Vec<Coin>,
 }
 
 impl Listing {
-    /// **If `Listing.fee_amount.is_some()`**
-    /// - Returns `Vec<CosmosMsg>` sending `Listing.fee_amount` to Com. Pool + `Listing.for_sale` to `Listing.claimant`
+    /// Who the Listing belongs to, the claimant once purchased, else the creator
+    #[must_use]
+    pub fn holder(&self) -> &Addr {
+        self.claimant.as_ref().unwrap_or(&self.creator)
+    }
+
+    /// The ask at `index`, errors if there isn't one
+    pub fn ask(&self, index: u32) -> Result<&GenericBalance, ContractError> {
+        self.asks.get(index as usize).ok_or(ContractError::InvalidAskIndex {
+            index,
+        })
+    }
+
+    /// `true` if the Listing has an expiration that's passed at `block`
+    #[must_use]
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expiration.map_or(false, |exp| exp.is_expired(block))
+    }
+
+    /// `true` if the Listing has no start, or its start has been reached at `block`
+    #[must_use]
+    pub fn has_started(&self, block: &BlockInfo) -> bool {
+        self.start.map_or(true, |start| start.is_triggered(block))
+    }
+
+    /// `true` if the Listing is finalized, started, not yet purchased & not expired at `block`
+    #[must_use]
+    pub fn is_purchasable(&self, block: &BlockInfo) -> bool {
+        self.status == Status::FinalizedReady
+            && self.claimant.is_none()
+            && self.has_started(block)
+            && !self.is_expired(block)
+    }
+
+    /// **If `Listing.fee_amount` is not empty**
+    /// - Returns `Vec<CosmosMsg>` sending `Listing.fee_amount` to `fee_recipient` + `Listing.for_sale` to `Listing.claimant`
     ///
-    /// **If `Listing.fee_amount.is_none()`**
+    /// **If `Listing.fee_amount` is empty**
     /// - Returns `Vec<CosmosMsg>` sending `Listing.for_sale` to `Listing.claimant`
-    pub fn withdraw_msgs(&self) -> Result<Vec<CosmosMsg>, ContractError> {
+    ///
+    /// `contract` is this contract's address, used as the Com. Pool depositor
+    pub fn withdraw_msgs(
+        &self,
+        contract: &Addr,
+        fee_recipient: &FeeRecipient,
+    ) -> Result<Vec<CosmosMsg>, ContractError> {
         // Get claimant (This will not called when Listing does not have claimant)
         let user = self.claimant.as_ref().ok_or_else(|| {
             ContractError::GenericError("Listing has not been purchased".to_string())
         })?;
 
-        match &self.fee_amount {
-            // No fee amount, send for_sale to user
-            None => send_tokens_cosmos(user, &self.for_sale).map_err(|_e| {
-                ContractError::GenericError("Error creating withdraw messages".to_string())
-            }),
-            // Some fee amount, send fee to CP & for_sale to user
-            Some(fee) => {
-                let mut user_msgs = send_tokens_cosmos(user, &self.for_sale).map_err(|_e| {
-                    ContractError::GenericError("Error creating withdraw messages".to_string())
-                })?;
-                let fee_msg = fee.get_cp_msg()?;
-                user_msgs.push(fee_msg);
-                Ok(user_msgs)
-            }
+        let mut user_msgs = send_tokens_cosmos(user, &self.for_sale).map_err(|_e| {
+            ContractError::GenericError("Error creating withdraw messages".to_string())
+        })?;
+
+        // Some fee amount, send fee to fee_recipient as well
+        if !self.fee_amount.is_empty() {
+            user_msgs.push(fee_recipient.fee_msg(&self.fee_amount, contract)?);
         }
+
+        Ok(user_msgs)
     }
 }
 
@@ -131,41 +579,334 @@ pub enum Status {
     Closed,
 }
 
+#[cw_serde]
+pub enum BuyerRestriction {
+    /// Only this address
+    Address(Addr),
+    /// Any of these addresses, up to `Limits.max_buyers`
+    Addresses(Vec<Addr>),
+    /// Members of this cw4 group, checked when buying
+    Cw4Group(Addr),
+    /// Holders of any NFT from this cw721 collection, checked when buying
+    NftHolder(Addr),
+}
+
+impl BuyerRestriction {
+    /// Errors if any address is invalid, or `Addresses` is empty, has duplicates
+    /// or more than `limits.max_buyers`
+    pub fn validate(
+        self,
+        api: &dyn Api,
+        limits: &Limits,
+    ) -> Result<BuyerRestriction, ContractError> {
+        let validate = |addr: Addr| {
+            api.addr_validate(addr.as_str()).map_err(|_| ContractError::InvalidAddressFormat)
+        };
+        match self {
+            BuyerRestriction::Address(addr) => validate(addr).map(BuyerRestriction::Address),
+            BuyerRestriction::Addresses(addrs) => {
+                if addrs.is_empty() || addrs.len() > limits.max_buyers as usize {
+                    return Err(ContractError::GenericError(format!(
+                        "Must have between 1 and {} buyers",
+                        limits.max_buyers
+                    )));
+                }
+                let addrs = addrs.into_iter().map(validate).collect::<Result<Vec<Addr>, _>>()?;
+                let unique = addrs.iter().collect::<BTreeSet<&Addr>>();
+                if unique.len() != addrs.len() {
+                    return Err(ContractError::GenericError(
+                        "Cannot contain duplicate buyers".to_string(),
+                    ));
+                }
+                Ok(BuyerRestriction::Addresses(addrs))
+            }
+            BuyerRestriction::Cw4Group(addr) => validate(addr).map(BuyerRestriction::Cw4Group),
+            BuyerRestriction::NftHolder(addr) => validate(addr).map(BuyerRestriction::NftHolder),
+        }
+    }
+
+    /// Addresses listed by `Address` or `Addresses`, empty for `Cw4Group` & `NftHolder`
+    #[must_use]
+    pub fn named(&self) -> &[Addr] {
+        match self {
+            BuyerRestriction::Address(addr) => std::slice::from_ref(addr),
+            BuyerRestriction::Addresses(addrs) => addrs,
+            BuyerRestriction::Cw4Group(_) | BuyerRestriction::NftHolder(_) => &[],
+        }
+    }
+
+    /// `true` if `buyer` is listed by `Address` or `Addresses`
+    #[must_use]
+    pub fn names(&self, buyer: &Addr) -> bool {
+        self.named().contains(buyer)
+    }
+
+    /// Errors if `buyer` does not meet this restriction
+    ///
+    /// Queries the group or collection contract for `Cw4Group` & `NftHolder`
+    pub fn check(&self, querier: &QuerierWrapper, buyer: &Addr) -> Result<(), ContractError> {
+        let allowed = match self {
+            BuyerRestriction::Address(_) | BuyerRestriction::Addresses(_) => self.names(buyer),
+            BuyerRestriction::Cw4Group(group) => {
+                let member: Cw4MemberResponse = querier.query_wasm_smart(
+                    group,
+                    &Cw4QueryMsg::Member {
+                        addr: buyer.to_string(),
+                        at_height: None,
+                    },
+                )?;
+                member.weight.is_some()
+            }
+            BuyerRestriction::NftHolder(collection) => {
+                let held: TokensResponse = querier.query_wasm_smart(
+                    collection,
+                    &Cw721QueryMsg::Tokens {
+                        owner: buyer.to_string(),
+                        start_after: None,
+                        limit: Some(1),
+                    },
+                )?;
+                !held.tokens.is_empty()
+            }
+        };
+        if !allowed {
+            return Err(ContractError::NotWhitelisted {});
+        }
+        Ok(())
+    }
+}
+
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // Buckets
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-pub const BUCKETS: Map<(Addr, u64), Bucket> = Map::new("buckets");
+pub struct BucketIndexes<'a> {
+    pub owner: MultiIndex<'a, Addr, Bucket, u64>,
+    // Empty address if the Bucket hasn't paid for a Listing
+    pub claimant: MultiIndex<'a, Addr, Bucket, u64>,
+}
+
+impl IndexList<Bucket> for BucketIndexes<'_> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Bucket>> + '_> {
+        let v: Vec<&dyn Index<Bucket>> = vec![&self.owner, &self.claimant];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Buckets keyed by id, which doesn't change when the Bucket pays for a Listing
+#[must_use]
+pub fn bucketz<'a>() -> IndexedMap<'a, u64, Bucket, BucketIndexes<'a>> {
+    let indexes = BucketIndexes {
+        owner: MultiIndex::new(|_pk, bucket| bucket.owner.clone(), "buckets_im", "buckets__owner"),
+        claimant: MultiIndex::new(
+            |_pk, bucket| claimant_key(bucket.claimant.as_ref()),
+            "buckets_im",
+            "buckets__claimant",
+        ),
+    };
+
+    IndexedMap::new("buckets_im", indexes)
+}
 
 #[cw_serde]
 pub struct Bucket {
+    /// Who deposited the funds
     pub owner: Addr,
     pub funds: GenericBalance,
-    pub fee_amount: Option<Coin>,
+    pub fee_amount: Vec<Coin>,
+    /// Set when the Bucket is used to buy a Listing, `funds` are then owed to the seller in full
+    pub paid_for: Option<u64>,
+    /// The seller once `paid_for` is set
+    pub claimant: Option<Addr>,
 }
 
 impl Bucket {
-    /// **If `Bucket.fee_amount.is_some()`**
-    /// - Returns `Vec<CosmosMsg>` sending `Bucket.fee_amount` to Com. Pool + `Bucket.funds` to `Bucket.owner`
+    /// Who can withdraw the Bucket, the claimant once it's paid for a Listing, else the owner
+    #[must_use]
+    pub fn holder(&self) -> &Addr {
+        self.claimant.as_ref().unwrap_or(&self.owner)
+    }
+
+    /// `true` once the Bucket has paid for a Listing, its funds can then only be withdrawn whole
     ///
-    /// **If `Bucket.fee_amount.is_none()`**
-    /// - Returns `Vec<CosmosMsg>` sending `Bucket.funds` to `Bucket.owner`
-    pub fn withdraw_msgs(&self) -> Result<Vec<CosmosMsg>, ContractError> {
-        match &self.fee_amount {
-            None => send_tokens_cosmos(&self.owner, &self.funds).map_err(|_e| {
-                ContractError::GenericError("Error creating withdraw messages".to_string())
-            }),
-            Some(fee) => {
-                let mut user_msgs = send_tokens_cosmos(&self.owner, &self.funds).map_err(|_e| {
-                    ContractError::GenericError("Error creating withdraw messages".to_string())
-                })?;
-                let fee_msg = fee.get_cp_msg()?;
-                user_msgs.push(fee_msg);
-                Ok(user_msgs)
-            }
+    /// Buckets migrated from v0.2.0 have no `paid_for`, a `fee_amount` marks them as seller proceeds
+    #[must_use]
+    pub fn is_committed(&self) -> bool {
+        self.paid_for.is_some() || !self.fee_amount.is_empty()
+    }
+
+    /// **If `Bucket.fee_amount` is not empty**
+    /// - Returns `Vec<CosmosMsg>` sending `Bucket.fee_amount` to `fee_recipient` + `Bucket.funds` to `Bucket.holder()`
+    ///
+    /// **If `Bucket.fee_amount` is empty**
+    /// - Returns `Vec<CosmosMsg>` sending `Bucket.funds` to `Bucket.holder()`
+    ///
+    /// `contract` is this contract's address, used as the Com. Pool depositor
+    pub fn withdraw_msgs(
+        &self,
+        contract: &Addr,
+        fee_recipient: &FeeRecipient,
+    ) -> Result<Vec<CosmosMsg>, ContractError> {
+        let mut user_msgs = send_tokens_cosmos(self.holder(), &self.funds).map_err(|_e| {
+            ContractError::GenericError("Error creating withdraw messages".to_string())
+        })?;
+
+        if !self.fee_amount.is_empty() {
+            user_msgs.push(fee_recipient.fee_msg(&self.fee_amount, contract)?);
+        }
+
+        Ok(user_msgs)
+    }
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Trades
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+pub struct TradeIndexes<'a> {
+    pub seller: MultiIndex<'a, Addr, Trade, u64>,
+    pub buyer: MultiIndex<'a, Addr, Trade, u64>,
+    pub time: MultiIndex<'a, u64, Trade, u64>,
+}
+
+impl IndexList<Trade> for TradeIndexes<'_> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Trade>> + '_> {
+        let v: Vec<&dyn Index<Trade>> = vec![&self.seller, &self.buyer, &self.time];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Every completed purchase keyed by `Trade.id`, entries are never removed
+#[must_use]
+pub fn trades<'a>() -> IndexedMap<'a, u64, Trade, TradeIndexes<'a>> {
+    let indexes = TradeIndexes {
+        seller: MultiIndex::new(|_pk, trade| trade.seller.clone(), "trades", "trades__seller"),
+        buyer: MultiIndex::new(|_pk, trade| trade.buyer.clone(), "trades", "trades__buyer"),
+        time: MultiIndex::new(|_pk, trade| trade.time.seconds(), "trades", "trades__time"),
+    };
+
+    IndexedMap::new("trades", indexes)
+}
+
+/// (cw721 contract, trade_id) for every collection traded on either side of a Trade
+pub const TRADE_COLLECTIONS: Map<(&Addr, u64), Empty> = Map::new("trades__collection");
+
+#[cw_serde]
+pub struct Trade {
+    pub id: u64,
+    pub listing_id: u64,
+    /// `None` if paid with funds sent in the purchase tx
+    pub bucket_id: Option<u64>,
+    pub seller: Addr,
+    pub buyer: Addr,
+    /// Listing contents, before fees
+    pub for_sale: GenericBalance,
+    /// Ask paid by the buyer, before fees
+    pub paid: GenericBalance,
+    /// Taken from `for_sale`
+    pub listing_fee: Vec<Coin>,
+    /// Taken from `paid`
+    pub ask_fee: Vec<Coin>,
+    pub time: Timestamp,
+    pub height: u64,
+}
+
+impl Trade {
+    /// cw721 contracts traded on either side
+    #[must_use]
+    pub fn collections(&self) -> BTreeSet<&Addr> {
+        self.for_sale.nfts.iter().chain(&self.paid.nfts).map(|nft| &nft.contract_address).collect()
+    }
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Stats
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Market counters, kept up to date by every execute that changes them
+///
+/// Per asset totals are kept in `NATIVE_VOLUME`, `CW20_VOLUME`, `NFTS_TRADED` & `FEE_TOTALS`
+/// so this stays the same size however many assets get traded
+#[cw_serde]
+#[derive(Default)]
+pub struct Stats {
+    /// Listings currently in storage
+    pub listings: ListingCounts,
+    pub trades: u64,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct ListingCounts {
+    pub being_prepared: u64,
+    pub finalized_ready: u64,
+    /// Purchased & waiting for the buyer to withdraw
+    pub closed: u64,
+}
+
+impl ListingCounts {
+    fn count_mut(&mut self, status: &Status) -> &mut u64 {
+        match status {
+            Status::BeingPrepared => &mut self.being_prepared,
+            Status::FinalizedReady => &mut self.finalized_ready,
+            Status::Closed => &mut self.closed,
+        }
+    }
+
+    pub fn add(&mut self, status: &Status) {
+        let count = self.count_mut(status);
+        *count = count.saturating_add(1);
+    }
+
+    pub fn remove(&mut self, status: &Status) {
+        let count = self.count_mut(status);
+        *count = count.saturating_sub(1);
+    }
+
+    pub fn moved(&mut self, from: &Status, to: &Status) {
+        self.remove(from);
+        self.add(to);
+    }
+}
+
+/// Adds both sides of `trade` to the volume totals & its fees to `FEE_TOTALS`
+pub fn add_trade_totals(storage: &mut dyn Storage, trade: &Trade) -> StdResult<()> {
+    for side in [&trade.for_sale, &trade.paid] {
+        for coin in &side.native {
+            add_to_total(storage, NATIVE_VOLUME, coin.denom.as_str(), coin.amount)?;
+        }
+        for token in &side.cw20 {
+            add_to_total(storage, CW20_VOLUME, &token.address, token.amount)?;
+        }
+        for nft in &side.nfts {
+            add_to_total(storage, NFTS_TRADED, &nft.contract_address, Uint128::one())?;
         }
     }
+
+    add_fee_totals(storage, &trade.listing_fee)?;
+    add_fee_totals(storage, &trade.ask_fee)
+}
+
+pub fn add_fee_totals(storage: &mut dyn Storage, fees: &[Coin]) -> StdResult<()> {
+    for coin in fees {
+        add_to_total(storage, FEE_TOTALS, coin.denom.as_str(), coin.amount)?;
+    }
+    Ok(())
+}
+
+fn add_to_total<'a, K: PrimaryKey<'a>>(
+    storage: &mut dyn Storage,
+    totals: Map<'a, K, Uint128>,
+    key: K,
+    amount: Uint128,
+) -> StdResult<()> {
+    totals.update(storage, key, |total| -> StdResult<Uint128> {
+        Ok(total.unwrap_or_default().saturating_add(amount))
+    })?;
+    Ok(())
 }
 
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -257,13 +998,108 @@ impl GenericBalance {
         self.nfts.push(nft);
     }
 
+    /// Removes every asset in `sub` from this **GenericBalance**
+    ///
+    /// - Natives & CW20s left with 0 are dropped
+    /// - Errors if any asset in `sub` is not held in full
+    pub fn subtract(&mut self, sub: &GenericBalance) -> Result<(), ContractError> {
+        for coin in &sub.native {
+            let Some(idx) = self.native.iter().position(|n| n.denom == coin.denom) else {
+                return Err(ContractError::InsufficientBalance(coin.denom.clone()));
+            };
+            self.native[idx].amount = self.native[idx]
+                .amount
+                .checked_sub(coin.amount)
+                .map_err(|_| ContractError::InsufficientBalance(coin.denom.clone()))?;
+            if self.native[idx].amount.is_zero() {
+                self.native.remove(idx);
+            }
+        }
+
+        for token in &sub.cw20 {
+            let Some(idx) = self.cw20.iter().position(|c| c.address == token.address) else {
+                return Err(ContractError::InsufficientBalance(token.address.to_string()));
+            };
+            self.cw20[idx].amount = self.cw20[idx]
+                .amount
+                .checked_sub(token.amount)
+                .map_err(|_| ContractError::InsufficientBalance(token.address.to_string()))?;
+            if self.cw20[idx].amount.is_zero() {
+                self.cw20.remove(idx);
+            }
+        }
+
+        for nft in &sub.nfts {
+            let Some(idx) = self.nfts.iter().position(|n| n == nft) else {
+                return Err(ContractError::InsufficientBalance(format!(
+                    "{} {}",
+                    nft.contract_address, nft.token_id
+                )));
+            };
+            self.nfts.remove(idx);
+        }
+
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.native.is_empty() && self.cw20.is_empty() && self.nfts.is_empty()
+    }
+
+    /// Errors if there are more than `limits.max_assets` Natives + CW20s + NFTs
+    pub fn check_limits(&self, limits: &Limits) -> Result<(), ContractError> {
+        let total = self.native.len() + self.cw20.len() + self.nfts.len();
+        if total > limits.max_assets as usize {
+            return Err(ContractError::TooManyAssets {
+                max: limits.max_assets,
+            });
+        }
+        Ok(())
+    }
+
+    /// Errors if any are true:
+    ///
+    /// - Any of the `normalized_check` cases
+    /// - Any Cw20 or NFT contract address is invalid or not normalized
+    /// - Any Cw20 contract doesn't answer `TokenInfo`, or NFT contract `ContractInfo`
+    /// - Any token is blocked by `Config.token_lists`
+    pub fn check_valid(&self, deps: Deps) -> Result<(), ContractError> {
+        self.normalized_check()?;
+
+        // Check addresses are valid & belong to the right kind of contract
+        for cw in &self.cw20 {
+            deps.api
+                .addr_validate(cw.address.as_str())
+                .map_err(|_| ContractError::InvalidAddressFormat)?;
+            deps.querier
+                .query_wasm_smart::<TokenInfoResponse>(&cw.address, &Cw20QueryMsg::TokenInfo {})
+                .map_err(|_| ContractError::InvalidTokenContract(cw.address.to_string()))?;
+        }
+        let collections =
+            self.nfts.iter().map(|nft| &nft.contract_address).collect::<BTreeSet<_>>();
+        for collection in collections {
+            deps.api
+                .addr_validate(collection.as_str())
+                .map_err(|_| ContractError::InvalidAddressFormat)?;
+            deps.querier
+                .query_wasm_smart::<ContractInfoResponse>(
+                    collection,
+                    &Cw721QueryMsg::ContractInfo {},
+                )
+                .map_err(|_| ContractError::InvalidTokenContract(collection.to_string()))?;
+        }
+
+        CONFIG.load(deps.storage)?.token_lists.check(self)
+    }
+
     /// Errors if any are true:
     ///
     /// - Any Native token amount == 0
     /// - Any Cw20 token amount == 0
     /// - Any duplicate Native Denom
     /// - Any duplicate Cw20 Contract addresses
-    pub fn check_valid(&self) -> Result<(), ContractError> {
+    /// - Any duplicate NFTs
+    pub fn normalized_check(&self) -> Result<(), ContractError> {
         // Check Natives for 0's
         if self.native.iter().any(|n| n.amount.is_zero()) {
             return Err(ContractError::GenericError("Cannot contain 0 value amounts".to_string()));
@@ -353,6 +1189,19 @@ pub fn genbal_cmp(one: &GenericBalance, two: &GenericBalance) -> Result<(), Cont
     Ok(())
 }
 
+/// Accepts 2 x `&GenericBalance` and checks that `one` holds at least everything in `two`
+/// - Fields do not need to be sorted
+/// - Returns what's left of `one` after taking out `two`
+/// - Errors if anything in `two` is missing from `one` or short
+pub fn genbal_contains(
+    one: &GenericBalance,
+    two: &GenericBalance,
+) -> Result<GenericBalance, ContractError> {
+    let mut surplus = one.clone();
+    surplus.subtract(two)?;
+    Ok(surplus)
+}
+
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // Cosmwasm types
@@ -403,19 +1252,88 @@ impl BalanceUtil for Balance {
     }
 }
 
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// cw4
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// The parts of the cw4 spec we query, saves depending on cw4 for two types
+
+#[cw_serde]
+pub enum Cw4QueryMsg {
+    Member {
+        addr: String,
+        at_height: Option<u64>,
+    },
+}
+
+#[cw_serde]
+pub struct Cw4MemberResponse {
+    /// `None` if not a member
+    pub weight: Option<u64>,
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Community Pool
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+pub const FUND_COMMUNITY_POOL_TYPE_URL: &str = "/cosmos.distribution.v1beta1.MsgFundCommunityPool";
+
+/// Protobuf `cosmos.base.v1beta1.Coin`
+#[derive(Clone, PartialEq, Eq, prost::Message)]
+pub struct ProtoCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+/// Protobuf `cosmos.distribution.v1beta1.MsgFundCommunityPool`
+#[derive(Clone, PartialEq, Eq, prost::Message)]
+pub struct MsgFundCommunityPool {
+    #[prost(message, repeated, tag = "1")]
+    pub amount: Vec<ProtoCoin>,
+    #[prost(string, tag = "2")]
+    pub depositor: String,
+}
+
 pub trait GetComPoolMsg {
-    fn get_cp_msg(&self) -> Result<CosmosMsg, ContractError>;
+    /// Returns a `CosmosMsg::Stargate` funding the Community Pool, with `depositor` as the sender
+    fn get_cp_msg(&self, depositor: &Addr) -> Result<CosmosMsg, ContractError>;
 }
 
 impl GetComPoolMsg for Coin {
-    fn get_cp_msg(&self) -> Result<CosmosMsg, ContractError> {
-        // PUPMOS - Insert solution for creating MsgFundCommunityPool msg
-        //
-        //
-        //
-        //
+    fn get_cp_msg(&self, depositor: &Addr) -> Result<CosmosMsg, ContractError> {
+        std::slice::from_ref(self).get_cp_msg(depositor)
+    }
+}
+
+impl GetComPoolMsg for [Coin] {
+    fn get_cp_msg(&self, depositor: &Addr) -> Result<CosmosMsg, ContractError> {
+        // Nothing to send, the chain would reject an empty deposit anyway
+        if self.is_empty() || self.iter().any(|c| c.amount.is_zero()) {
+            return Err(ContractError::FeeCalc);
+        }
+
+        // sdk.Coins must be sorted by denom to pass ValidateBasic
+        let mut coins = self.to_vec();
+        coins.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+        let msg = MsgFundCommunityPool {
+            amount: coins
+                .into_iter()
+                .map(|c| ProtoCoin {
+                    denom: c.denom,
+                    amount: c.amount.to_string(),
+                })
+                .collect(),
+            depositor: depositor.to_string(),
+        };
 
-        Err(ContractError::GenericError("Placeholder".to_string()))
+        Ok(CosmosMsg::Stargate {
+            type_url: FUND_COMMUNITY_POOL_TYPE_URL.to_string(),
+            value: Binary::from(prost::Message::encode_to_vec(&msg)),
+        })
     }
 }
 
@@ -475,11 +1393,9 @@ mod state_tests {
             nfts: nfts_x.clone(),
         };
 
-        let _res = genbal_cmp(&gen_bal_main, &gen_bal_x).expect(&here(
-            "Reordered should be equal",
-            line!(),
-            column!(),
-        ));
+        genbal_cmp(&gen_bal_main, &gen_bal_x).unwrap_or_else(|_| {
+            panic!("{}", here("Reordered should be equal", line!(), column!()))
+        });
 
         //let _resx = fake(&gen_bal_main, &gen_bal_x).expect(&here("Reordered should be equal", line!(), column!()));
 
@@ -706,5 +1622,166 @@ mod state_tests {
         };
         let _res =
             genbal_cmp(&gen_bal_main, &gen_bal_mph).expect_err(&here("nft", line!(), column!()));
+
+        //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+        // Containment, one must hold at least two
+        //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+        let surplus = genbal_contains(&gen_bal_main, &gen_bal_x)
+            .unwrap_or_else(|_| panic!("{}", here("Reordered contains", line!(), column!())));
+        assert!(surplus.is_empty());
+
+        // Extra NFT & more of a native are left over
+        let surplus = genbal_contains(&gen_bal_hh, &gen_bal_main)
+            .unwrap_or_else(|_| panic!("{}", here("Extra NFT contains", line!(), column!())));
+        assert_eq!(surplus.nfts, vec![nft("dogs", "35")]);
+        assert!(surplus.native.is_empty() && surplus.cw20.is_empty());
+
+        let gen_bal_more = GenericBalance {
+            native: vec![coin(201, "ATOM"), coin(100, "JUNO"), coin(300, "OSMO"), coin(5, "STARS")],
+            cw20: cw20s_x.clone(),
+            nfts: nfts_x.clone(),
+        };
+        let surplus = genbal_contains(&gen_bal_more, &gen_bal_main)
+            .unwrap_or_else(|_| panic!("{}", here("More natives contains", line!(), column!())));
+        assert_eq!(surplus.native, vec![coin(1, "ATOM"), coin(5, "STARS")]);
+
+        // Anything missing or short fails
+        for (short, ctx) in [
+            (&gen_bal_lv, "nft"),
+            (&gen_bal_mph, "nft"),
+            (&gen_bal_mm, "nft"),
+            (&gen_bal_y, "native"),
+            (&gen_bal_l, "native"),
+            (&gen_bal_xx, "cw"),
+            (&gen_bal_a, "cw"),
+        ] {
+            let _res =
+                genbal_contains(short, &gen_bal_main).expect_err(&here(ctx, line!(), column!()));
+        }
+
+        // Less is contained in more
+        let surplus = genbal_contains(&gen_bal_main, &gen_bal_lv)
+            .unwrap_or_else(|_| panic!("{}", here("One less contained", line!(), column!())));
+        assert_eq!(surplus.nfts, vec![nft("boredcats", "30")]);
+    }
+
+    #[test]
+    fn community_pool_msg_encoding() {
+        let fee = coin(25_000, "ujunox");
+        let depositor = Addr::unchecked("contract5");
+
+        let CosmosMsg::Stargate {
+            type_url,
+            value,
+        } = fee.get_cp_msg(&depositor).unwrap()
+        else {
+            panic!("{}", here("Expected a Stargate msg", line!(), column!()));
+        };
+        assert_eq!(type_url, "/cosmos.distribution.v1beta1.MsgFundCommunityPool");
+
+        #[rustfmt::skip]
+        let expected: Vec<u8> = vec![
+            // amount (field 1, repeated Coin), 15 bytes
+            0x0a, 0x0f,
+                // denom (field 1) "ujunox"
+                0x0a, 0x06, b'u', b'j', b'u', b'n', b'o', b'x',
+                // amount (field 2) "25000"
+                0x12, 0x05, b'2', b'5', b'0', b'0', b'0',
+            // depositor (field 2) "contract5"
+            0x12, 0x09, b'c', b'o', b'n', b't', b'r', b'a', b'c', b't', b'5',
+        ];
+        assert_eq!(value.as_slice(), expected.as_slice());
+
+        // Round trip
+        let decoded: MsgFundCommunityPool = prost::Message::decode(value.as_slice()).unwrap();
+        assert_eq!(
+            decoded,
+            MsgFundCommunityPool {
+                amount: vec![ProtoCoin {
+                    denom: "ujunox".to_string(),
+                    amount: "25000".to_string(),
+                }],
+                depositor: "contract5".to_string(),
+            }
+        );
+
+        // Multiple fee coins go out in one msg, sorted by denom
+        let fees = [coin(2, "uusdc"), coin(1, "ujunox")];
+        let CosmosMsg::Stargate {
+            value,
+            ..
+        } = fees.get_cp_msg(&depositor).unwrap()
+        else {
+            panic!("{}", here("Expected a Stargate msg", line!(), column!()));
+        };
+        let decoded: MsgFundCommunityPool = prost::Message::decode(value.as_slice()).unwrap();
+        let denoms: Vec<&str> = decoded.amount.iter().map(|c| c.denom.as_str()).collect();
+        assert_eq!(denoms, vec!["ujunox", "uusdc"]);
+
+        // Zero fee should never produce a msg
+        let _res = coin(0, "ujunox").get_cp_msg(&depositor).expect_err(&here(
+            "Zero fee",
+            line!(),
+            column!(),
+        ));
+    }
+
+    #[test]
+    fn genericbalance_subtract() {
+        let mut bal = GenericBalance {
+            native: vec![coin(100, "JUNO"), coin(200, "ATOM")],
+            cw20: vec![cw20("foo", 1), cw20("bar", 2)],
+            nfts: vec![nft("boredcats", "30"), nft("dogs", "31")],
+        };
+
+        // Partial amounts stay, emptied tokens are dropped
+        bal.subtract(&GenericBalance {
+            native: vec![coin(40, "JUNO"), coin(200, "ATOM")],
+            cw20: vec![cw20("bar", 1)],
+            nfts: vec![nft("dogs", "31")],
+        })
+        .unwrap();
+        assert_eq!(
+            bal,
+            GenericBalance {
+                native: vec![coin(60, "JUNO")],
+                cw20: vec![cw20("foo", 1), cw20("bar", 1)],
+                nfts: vec![nft("boredcats", "30")],
+            }
+        );
+
+        // Anything not held in full errors
+        let missing = [
+            GenericBalance {
+                native: vec![coin(61, "JUNO")],
+                cw20: vec![],
+                nfts: vec![],
+            },
+            GenericBalance {
+                native: vec![coin(1, "ATOM")],
+                cw20: vec![],
+                nfts: vec![],
+            },
+            GenericBalance {
+                native: vec![],
+                cw20: vec![cw20("baz", 1)],
+                nfts: vec![],
+            },
+            GenericBalance {
+                native: vec![],
+                cw20: vec![],
+                nfts: vec![nft("dogs", "31")],
+            },
+        ];
+        for sub in missing {
+            let _res = bal.clone().subtract(&sub).expect_err(&here(
+                "Subtract missing",
+                line!(),
+                column!(),
+            ));
+        }
+
+        bal.subtract(&bal.clone()).unwrap();
+        assert!(bal.is_empty(), "{}", here("Subtract all", line!(), column!()));
     }
 }
@@ -64,66 +64,57 @@ pub fn send_tokens_cosmos(to: &Addr, balance: &GenericBalance) -> StdResult<Vec<
     Ok(msgs)
 }
 
-/// Accepts current FeeDenom & GenericBalance, returns one of the following
+/// Accepts current FeeConfig & GenericBalance, returns one of the following
 ///
 ///
-/// **If FeeDenom is not in GenericBalance || fifty_pips floored is zero**
-/// - Returns Fee Coin as None +
+/// **If no fee denom is in GenericBalance || every fee floored is zero**
+/// - Returns Fee Coins as empty +
 /// - Returns Balance unchanged
-/// - `Ok((None, GenericBalance))`
+/// - `Ok((vec![], GenericBalance))`
 ///
 ///
-/// **If FeeDenom is in Balance && fifty_pips floored is not zero**
-/// - Returns FeeCoin as fifty_pips of Fee Denom in Balance
-/// - Returns Balance as Balance - FeeCoin
-/// - Returns Ok((Some(Coin), Balance))
+/// **For each fee denom in Balance**
+/// - Fee is `bps` of the amount in Balance, raised to `min_fee` if set (never more than the amount)
+/// - Returns Fee Coins as the non zero fees
+/// - Returns Balance as Balance - Fee Coins
+/// - Returns Ok((Vec<Coin>, Balance))
 ///
 ///
 /// **Returns StdError on int overflow**
-pub fn calc_fee_coin(
-    fee_type: &FeeDenom,
+pub fn calc_fee_coins(
+    fee_config: &FeeConfig,
     balance: &GenericBalance,
-) -> StdResult<(Option<Coin>, GenericBalance)> {
-    // Get the current fee denom to check for
-    let fee_denom = fee_type.value();
-
-    // Find the fee denom in balance
-    let fee_in_balance = balance.native.iter().find(|n| n.denom == fee_denom);
-
-    // Calculate Fee Amount & return
-    match fee_in_balance {
-        // If fee_denom not found, return (None, balance)
-        None => Ok((None, balance.to_owned())),
-
-        // If fee_denom found, calculate fee coin
-        Some(fee) => {
-            // Calc 0.5% of fee_denom found
-            let fifty_pips = fee.amount.multiply_ratio(5_u128, 1000_u128);
-
-            // small amounts (like 1ujuno) will be 0, so return None
-            if fifty_pips.is_zero() {
-                return Ok((None, balance.to_owned()));
-            }
-
-            // Create Fee Coin
-            let fee_coin = coin(fifty_pips.u128(), fee_denom.clone());
-
-            // Subtract fee amount from the fee coin found in balance
-            // Sub fee amount from balance_fee coin amount
-            let amount_sub_fee = fee.amount.checked_sub(fifty_pips)?;
-
-            // Create GenericBalance with fee amount removed
-            let balance_with_fee_removed = {
-                let mut x = balance.clone();
-                x.native.retain(|n| n.denom != fee_denom);
-                x.native.append(&mut coins(amount_sub_fee.u128(), fee_denom));
-                x
-            };
+) -> StdResult<(Vec<Coin>, GenericBalance)> {
+    let mut fee_coins: Vec<Coin> = vec![];
+    let mut balance_with_fee_removed = balance.clone();
+
+    for native in balance_with_fee_removed.native.iter_mut() {
+        // Skip anything that isn't a fee denom
+        let Some(fee_denom) = fee_config.get(&native.denom) else {
+            continue;
+        };
+
+        // Calc bps of fee_denom found
+        let rate_fee = native.amount.multiply_ratio(fee_denom.bps, MAX_FEE_BPS);
+
+        // Raise to min_fee, but never take more than what's there
+        let fee = fee_denom.min_fee.map_or(rate_fee, |min| rate_fee.max(min)).min(native.amount);
 
-            // Return (Fee, Balance_minus_fee)
-            Ok((Some(fee_coin), balance_with_fee_removed))
+        // small amounts (like 1ujuno) will be 0, so skip
+        if fee.is_zero() {
+            continue;
         }
+
+        // Sub fee amount from balance_fee coin amount
+        native.amount = native.amount.checked_sub(fee)?;
+        fee_coins.push(coin(fee.u128(), native.denom.clone()));
     }
+
+    // Don't leave 0 value coins behind if the whole amount was taken
+    balance_with_fee_removed.native.retain(|n| !n.amount.is_zero());
+
+    // Return (Fees, Balance_minus_fees)
+    Ok((fee_coins, balance_with_fee_removed))
 }
 
 // Accepts a `GenericBalance` and calculates the fee to be paid, based on the current fee denom